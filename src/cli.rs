@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+/// Command line options of the `jumpar` binary.
+#[derive(Debug, Default)]
+pub struct Args {
+    /// `--save <path>`: use this save file instead of the one in the platform data directory.
+    pub save_path: Option<PathBuf>,
+    /// `--edit`: start in the level editor.
+    pub edit: bool,
+    /// `--generate`: play a procedurally generated level.
+    pub generate: bool,
+    /// `--seed <n>`: seed of the generated level; random when omitted.
+    pub seed: Option<u64>,
+    /// `--difficulty <0..1>`: difficulty of the generated level.
+    pub difficulty: Option<f32>,
+    /// `--out <path>`: where the generated level JSON is written.
+    pub out: Option<String>,
+    /// `--theme <path>`: theme JSON for levels that don't name their own.
+    pub theme: Option<String>,
+    /// `--mute`: run without sound effects, music or an audio device.
+    pub mute: bool,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--save" => {
+                    let path = args.next().ok_or("--save needs a path")?;
+                    parsed.save_path = Some(PathBuf::from(path));
+                }
+                "--edit" => parsed.edit = true,
+                "--generate" => parsed.generate = true,
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed needs a number")?;
+                    let seed = seed.parse().map_err(|_| format!("Invalid seed: {seed}"))?;
+                    parsed.seed = Some(seed);
+                }
+                "--difficulty" => {
+                    let difficulty = args.next().ok_or("--difficulty needs a number")?;
+                    let difficulty = difficulty
+                        .parse()
+                        .ok()
+                        .filter(|difficulty| (0.0..=1.0).contains(difficulty))
+                        .ok_or(format!("Difficulty must be between 0 and 1: {difficulty}"))?;
+                    parsed.difficulty = Some(difficulty);
+                }
+                "--out" => {
+                    let path = args.next().ok_or("--out needs a path")?;
+                    parsed.out = Some(path);
+                }
+                "--theme" => {
+                    let path = args.next().ok_or("--theme needs a path")?;
+                    parsed.theme = Some(path);
+                }
+                "--mute" => parsed.mute = true,
+                _ => return Err(format!("Unknown argument: {arg}")),
+            }
+        }
+        let generator_options =
+            parsed.seed.is_some() || parsed.difficulty.is_some() || parsed.out.is_some();
+        if generator_options && !parsed.generate {
+            return Err("--seed, --difficulty and --out need --generate".to_string());
+        }
+        Ok(parsed)
+    }
+}
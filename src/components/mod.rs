@@ -1,30 +1,154 @@
 use crate::resources;
-use bevy::prelude::*;
+use crate::resources::key_bindings::Action;
+use crate::spawners;
+use bevy::{prelude::*, window::WindowMode};
 pub mod player;
 
 #[derive(Component)]
 pub struct FpsText;
 
+#[derive(Component)]
+pub struct DeathText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct TimeText;
+
+#[derive(Component)]
+pub struct ResultsScreen;
+
+#[derive(Component)]
+pub struct EditorStatusText;
+
+#[derive(Component)]
+pub struct HeightText;
+
+/// Marks what an endless run spawns outside the level, e.g. its HUD.
+#[derive(Component)]
+pub struct EndlessEntity;
+
+/// What a menu button does when pressed.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuButton {
+    Play,
+    LevelSelect,
+    Settings,
+    Quit,
+    /// Back to the main menu.
+    Back,
+    /// Index into the level list.
+    PlayLevel(usize),
+    /// Starts an endless mode run.
+    Endless,
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+
+/// A settings menu entry; pressing it cycles the setting to its next value.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsButton {
+    WindowMode,
+    Resolution,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    ShowFps,
+    /// Waits for the next key press and binds it to the action.
+    Rebind(Action),
+}
+
+impl SettingsButton {
+    pub fn label(
+        &self,
+        settings: &resources::save_data::Settings,
+        rebinding: Option<Action>,
+    ) -> String {
+        let on_off = |value: bool| if value { "On" } else { "Off" };
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+        match self {
+            SettingsButton::WindowMode => {
+                let mode = match settings.window_mode {
+                    WindowMode::Windowed => "Windowed",
+                    WindowMode::BorderlessFullscreen => "Borderless",
+                    WindowMode::SizedFullscreen | WindowMode::Fullscreen => "Fullscreen",
+                };
+                format!("Window: {mode}")
+            }
+            SettingsButton::Resolution => {
+                let [width, height] = settings.resolution;
+                format!("Resolution: {width}x{height}")
+            }
+            SettingsButton::Vsync => format!("VSync: {}", on_off(settings.vsync)),
+            SettingsButton::MasterVolume => format!("Master: {}", percent(settings.master_volume)),
+            SettingsButton::MusicVolume => format!("Music: {}", percent(settings.music_volume)),
+            SettingsButton::SfxVolume => format!("SFX: {}", percent(settings.sfx_volume)),
+            SettingsButton::ShowFps => format!("FPS counter: {}", on_off(settings.show_fps)),
+            SettingsButton::Rebind(action) if rebinding == Some(*action) => {
+                format!("{}: press a key", action.label())
+            }
+            SettingsButton::Rebind(action) => {
+                format!(
+                    "{}: {:?}",
+                    action.label(),
+                    settings.key_bindings.key(*action)
+                )
+            }
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Pad;
 
 #[derive(Component)]
-pub struct Goal;
+pub struct Goal {
+    pub requires_all_collectibles: bool,
+}
+
+/// Marks everything spawned from the level JSON, so a restart can despawn it.
+#[derive(Component)]
+pub struct LevelEntity;
+
+#[derive(Component)]
+pub struct Checkpoint {
+    pub active: bool,
+}
 
 #[derive(Bundle)]
 pub struct WallBundle {
     pub collider: Collider,
+    pub wall: Wall,
     pub sprite_bundle: SpriteBundle,
 }
 
 #[derive(Component)]
 pub struct PlayerState {
     pub grounded: bool,
+    /// `-1.0` or `1.0` while the player touches a wall or block on
+    /// their left or right, `0.0` otherwise.
+    pub wall_side: f32,
 }
 
 #[derive(Component)]
 pub struct Player;
 
+/// Camera that follows the player through levels larger than the screen.
+#[derive(Component, Default)]
+pub struct FollowCamera {
+    /// Trails the player, staying within the dead zone around them.
+    pub focus: Vec2,
+    /// Current horizontal lead, eased towards the direction of travel.
+    pub look_ahead: f32,
+    /// Smoothed camera position before shake.
+    pub position: Vec2,
+    /// Shake strength from 0.0 to 1.0; settles over time.
+    pub trauma: f32,
+}
+
 #[derive(Component)]
 pub struct Mass(pub f32);
 
@@ -37,33 +161,104 @@ pub struct Collider;
 #[derive(Component)]
 pub struct Direction(pub f32);
 
+#[derive(Component)]
+pub struct Hazard;
+
+#[derive(Component)]
+pub struct Collectible {
+    pub points: u32,
+}
+
+/// Keeps an entity at a fixed offset from the block with the given index.
+#[derive(Component)]
+pub struct RidesBlock {
+    pub block: usize,
+    pub offset: Vec2,
+}
+
+/// A pooled particle. Only visible particles are alive.
+#[derive(Component, Default)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+    pub gravity: f32,
+    pub drag: f32,
+    /// Size at birth and at death.
+    pub size: [f32; 2],
+    pub color: Color,
+    pub fade: bool,
+}
+
+/// One of the level's parallax backgrounds.
+#[derive(Component)]
+pub struct ParallaxLayer {
+    pub background: resources::json_reader::Background,
+    /// How far the layer's `scroll` has carried it so far.
+    pub scrolled: Vec2,
+}
+
+/// The level's looping background music. Outlives the level, so levels with
+/// the same music play it on without restarting.
+#[derive(Component)]
+pub struct LevelMusic {
+    /// Audio file relative to the `assets` directory.
+    pub path: String,
+}
+
+/// Non-solid area that reports player contact. Holds the full width and height.
+#[derive(Component)]
+pub struct Sensor(pub Vec2);
+
+#[derive(Bundle)]
+pub struct HazardBundle {
+    pub hazard: Hazard,
+    pub sensor: Sensor,
+    pub sprite_bundle: SpriteBundle,
+}
+
 #[derive(Component)]
 pub struct Block(pub usize);
 
+/// Full width and height of a block, so systems don't have to look the block
+/// up in the config.
+#[derive(Component)]
+pub struct BlockSize(pub Vec2);
+
 #[derive(Bundle)]
 pub struct BlockBundle {
     pub collider: Collider,
     pub sprite_bundle: SpriteBundle,
 }
 
-pub enum WallLocation {
-    Bottom,
-    Left,
-    Right,
+/// A solid wall. Holds its index in `Config::walls` and its corners relative to
+/// its translation, counterclockwise.
+#[derive(Component)]
+pub struct Wall {
+    pub index: usize,
+    pub points: Vec<Vec2>,
 }
 
 impl WallBundle {
-    pub fn new(location: WallLocation, config: &Res<resources::json_reader::Config>) -> WallBundle {
+    pub fn new(index: usize, wall: &resources::json_reader::Wall, color: Color) -> WallBundle {
+        let bounds = wall.bounds();
+        let center = bounds.center();
+        let (custom_size, color) = match wall {
+            resources::json_reader::Wall::Rect { .. } => (Some(bounds.size()), color),
+            // Drawn by the mesh `mesh_polygon_walls_system` adds instead.
+            resources::json_reader::Wall::Polygon { .. } => (None, Color::NONE),
+        };
         WallBundle {
             collider: Collider,
+            wall: Wall {
+                index,
+                points: wall.points().iter().map(|point| *point - center).collect(),
+            },
             sprite_bundle: SpriteBundle {
-                transform: Transform {
-                    translation: location.position(&config).extend(0.0),
-                    scale: location.size(&config).extend(1.0),
-                    ..default()
-                },
+                transform: Transform::from_translation(center.extend(spawners::LEVEL_Z)),
                 sprite: Sprite {
-                    color: Color::srgb_from_array(config.wall_params.color),
+                    color,
+                    custom_size,
                     ..default()
                 },
                 ..default()
@@ -72,42 +267,61 @@ impl WallBundle {
     }
 }
 
-impl WallLocation {
-    fn position(&self, config: &Res<resources::json_reader::Config>) -> Vec2 {
-        match self {
-            WallLocation::Bottom => Vec2::new(0.0, config.wall_params.bottom_y),
-            WallLocation::Left => Vec2::new(config.wall_params.left_x, 0.0),
-            WallLocation::Right => Vec2::new(config.wall_params.right_x, 0.0),
+impl HazardBundle {
+    pub fn new(hazard: &resources::json_reader::Hazard, color: Color) -> HazardBundle {
+        let visibility = match hazard.kind {
+            resources::json_reader::HazardKind::KillZone => Visibility::Hidden,
+            _ => Visibility::Inherited,
+        };
+        let size = Vec2::new(hazard.w, hazard.h);
+        HazardBundle {
+            hazard: Hazard,
+            sensor: Sensor(size),
+            sprite_bundle: SpriteBundle {
+                transform: Transform::from_xyz(hazard.x, hazard.y, spawners::LEVEL_Z),
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                visibility,
+                ..default()
+            },
         }
     }
-    fn size(&self, config: &Res<resources::json_reader::Config>) -> Vec2 {
-        let level_width = config.wall_params.right_x - config.wall_params.left_x;
-        assert!(level_width > 0.0);
-        match self {
-            WallLocation::Left | WallLocation::Right => Vec2::new(
-                config.wall_params.thickness,
-                level_width + config.wall_params.thickness,
-            ),
-            WallLocation::Bottom => Vec2::new(
-                level_width + config.wall_params.thickness,
-                config.wall_params.thickness,
-            ),
+
+    /// Lava in place of the bottom wall.
+    pub fn lava_floor(
+        config: &Res<resources::json_reader::Config>,
+        theme: &Res<resources::theme::Theme>,
+    ) -> HazardBundle {
+        let floor = config.wall_params.floor();
+        let size = floor.size();
+        HazardBundle {
+            hazard: Hazard,
+            sensor: Sensor(size),
+            sprite_bundle: SpriteBundle {
+                transform: Transform::from_translation(floor.center().extend(spawners::LEVEL_Z)),
+                sprite: Sprite {
+                    color: Color::srgb_from_array(theme.lava),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
         }
     }
 }
 
 impl BlockBundle {
-    pub fn new(block: &resources::json_reader::Block) -> BlockBundle {
+    pub fn new(block: &resources::json_reader::Block, color: Color) -> BlockBundle {
         BlockBundle {
             collider: Collider,
             sprite_bundle: SpriteBundle {
-                transform: Transform {
-                    translation: Vec3::new(block.x, block.y, 0.0),
-                    scale: Vec3::new(block.w, block.h, 0.0),
-                    ..default()
-                },
+                transform: Transform::from_xyz(block.x, block.y, spawners::LEVEL_Z),
                 sprite: Sprite {
-                    color: Color::srgb(0.5, 0.5, 0.5),
+                    color,
+                    custom_size: Some(Vec2::new(block.w, block.h)),
                     ..default()
                 },
                 ..default()
@@ -1 +1,41 @@
+use bevy::prelude::*;
+use serde::Deserialize;
 
+/// What the player is doing, as far as their animation is concerned.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Run,
+    Jump,
+    Fall,
+    /// Just touched down; plays once before idle or run.
+    Land,
+    /// Falling while pushing against a wall.
+    WallSlide,
+}
+
+impl AnimationState {
+    /// The state whose animation stands in for this one's if it has none.
+    pub fn fallback(self) -> Option<AnimationState> {
+        match self {
+            AnimationState::Idle => None,
+            AnimationState::Run | AnimationState::Land | AnimationState::Jump => {
+                Some(AnimationState::Idle)
+            }
+            AnimationState::Fall => Some(AnimationState::Jump),
+            AnimationState::WallSlide => Some(AnimationState::Fall),
+        }
+    }
+}
+
+/// The player's current animation. Sheets are drawn facing right and
+/// flipped when the player faces left.
+#[derive(Component, Default)]
+pub struct PlayerAnimation {
+    pub state: AnimationState,
+    /// Seconds since `state` was entered.
+    pub elapsed: f32,
+    pub facing_left: bool,
+}
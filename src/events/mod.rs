@@ -1,9 +1,13 @@
 use bevy::prelude::*;
 
+/// Sent for every block or wall the player overlaps after moving.
 #[derive(Event)]
 pub struct Collision {
-    pub block_index: usize,
+    pub entity: Entity,
+    /// The side of the block or wall the player ran into.
     pub side: CollisionSide,
+    /// Moves the player out of the block or wall.
+    pub push: Vec2,
 }
 
 #[derive(Event, Debug, PartialEq, Eq, Copy, Clone)]
@@ -13,3 +17,52 @@ pub enum CollisionSide {
     Top,
     Bottom,
 }
+
+/// Sent every tick the player overlaps an entity with a `Sensor`.
+#[derive(Event)]
+pub struct SensorContact {
+    pub entity: Entity,
+}
+
+#[derive(Event)]
+pub struct PlayerDied {
+    /// Where the player was when they died.
+    pub position: Vec2,
+}
+
+/// Sent when the player jumps off the floor or a block.
+#[derive(Event)]
+pub struct PlayerJumped;
+
+/// Sent when the player lands on the floor or a block.
+#[derive(Event)]
+pub struct PlayerLanded {
+    /// Downward speed just before touching down.
+    pub speed: f32,
+}
+
+/// Sent when the player picks up a coin or gem.
+#[derive(Event)]
+pub struct CollectiblePickedUp {
+    pub points: u32,
+}
+
+/// Despawns the level and spawns it again from the current `Config`.
+#[derive(Event)]
+pub struct RestartLevel;
+
+/// Sent once, when the player first touches an unlocked goal.
+#[derive(Event)]
+pub struct GoalReached {
+    pub goal: Entity,
+}
+
+/// Starts a new endless mode run.
+#[derive(Event)]
+pub struct StartEndless;
+
+/// Replaces the current `Config` with the level at `path` and restarts.
+#[derive(Event)]
+pub struct LoadLevel {
+    pub path: String,
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use std::path::PathBuf;
+
+pub mod cli;
+pub mod components;
+pub mod events;
+pub mod plugins;
+pub mod resources;
+pub mod spawners;
+pub mod states;
+pub mod systems;
+
+/// Adds the whole game to an app: level loading, physics, player control, the
+/// follow camera, parallax backgrounds, player animations, particle effects,
+/// sound and music, moving blocks, hazards, checkpoints, collectibles, the
+/// goal, the results screen, save data, menus, the HUD, the level editor and
+/// endless mode.
+///
+/// Windowing and rendering are left to the host app, so `DefaultPlugins`
+/// (or an equivalent set) must be added before this plugin. Apps that only
+/// want some of the pieces can add the plugins from [`plugins`] directly.
+pub struct JumparPlugin {
+    /// Path of the level JSON, relative to the current working directory.
+    pub config_path: String,
+    /// Overrides the default save location in the platform data directory.
+    pub save_path: Option<PathBuf>,
+    /// Start in the level editor instead of the main menu.
+    pub edit: bool,
+    /// Start playing `config_path` straight away instead of showing the main menu.
+    pub skip_menu: bool,
+    /// Theme JSON for levels that don't name their own.
+    pub theme_path: Option<String>,
+    /// Play sound effects and music. Turn off when running without an audio
+    /// device.
+    pub audio: bool,
+}
+
+impl Default for JumparPlugin {
+    fn default() -> Self {
+        JumparPlugin {
+            config_path: "assets/config.json".to_string(),
+            save_path: None,
+            edit: false,
+            skip_menu: false,
+            theme_path: None,
+            audio: true,
+        }
+    }
+}
+
+impl Plugin for JumparPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            plugins::LevelPlugin {
+                config_path: self.config_path.clone(),
+                theme_path: self.theme_path.clone(),
+                ..default()
+            },
+            plugins::PhysicsPlugin::default(),
+            plugins::PlayerPlugin::default(),
+            plugins::CameraPlugin,
+            plugins::ParallaxPlugin,
+            plugins::AnimationPlugin,
+            plugins::ParticlePlugin::default(),
+            plugins::SoundPlugin {
+                enabled: self.audio,
+                ..default()
+            },
+            plugins::BlocksPlugin,
+            plugins::HazardPlugin,
+            plugins::CheckpointPlugin,
+            plugins::CollectiblePlugin,
+            plugins::GoalPlugin,
+            plugins::ResultsPlugin,
+            (
+                plugins::SavePlugin {
+                    path: self.save_path.clone(),
+                },
+                plugins::MenuPlugin,
+                plugins::HudPlugin::default(),
+                plugins::DebugPlugin::default(),
+                plugins::EditorPlugin {
+                    start_in_editor: self.edit,
+                },
+                plugins::EndlessPlugin,
+            ),
+        ));
+        #[cfg(feature = "inspector")]
+        app.add_plugins(plugins::InspectorPlugin);
+        if self.skip_menu && !self.edit {
+            app.insert_state(states::GameState::Playing);
+        }
+    }
+}
+
+pub fn setup(
+    mut commands: Commands,
+    config: Res<resources::json_reader::Config>,
+    theme: Res<resources::theme::Theme>,
+) {
+    commands.spawn((
+        Camera2dBundle::default(),
+        components::FollowCamera::default(),
+    ));
+    spawners::spawn_level(&mut commands, &config, &theme);
+}
@@ -1,42 +1,58 @@
-use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
+use bevy::audio::AudioPlugin;
+use bevy::prelude::*;
+use jumpar::{
+    cli::Args,
+    resources::{json_reader, level_generator},
+    JumparPlugin,
+};
 
-mod components;
-mod events;
-mod plugins;
-mod resources;
-mod spawners;
-mod systems;
+/// Where `--generate` writes the level unless `--out` is given.
+const GENERATED_LEVEL_PATH: &str = "assets/levels/generated.json";
 
 fn main() {
-    App::new()
-        .add_plugins((DefaultPlugins, FrameTimeDiagnosticsPlugin))
-        .insert_resource(resources::json_reader::JsonFilePath(
-            "assets/config.json".to_string(),
-        ))
-        .add_event::<events::Collision>()
-        .add_systems(Startup, (resources::json_reader::read_json, setup).chain())
-        .add_plugins(plugins::GeneralPlugin)
-        .add_systems(
-            FixedUpdate,
-            (
-                systems::ui_systems::text_update_system,
-                systems::goal_systems::goal_system,
-                systems::block_systems::move_block_system,
-            )
-                .chain(),
-        )
-        .run();
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
+    let mut plugin = JumparPlugin {
+        save_path: args.save_path.clone(),
+        edit: args.edit,
+        theme_path: args.theme.clone(),
+        audio: !args.mute,
+        ..default()
+    };
+    if args.generate {
+        plugin.config_path = generate_level(&args).unwrap_or_else(|err| {
+            eprintln!("Unable to generate level: {err:#}");
+            std::process::exit(1);
+        });
+        plugin.skip_menu = true;
+    }
+
+    let default_plugins = if args.mute {
+        DefaultPlugins.build().disable::<AudioPlugin>()
+    } else {
+        DefaultPlugins.build()
+    };
+    App::new().add_plugins((default_plugins, plugin)).run();
 }
 
-fn setup(
-    mut commands: Commands,
-    config: Res<resources::json_reader::Config>,
-    asset_server: Res<AssetServer>,
-) {
-    commands.spawn(Camera2dBundle::default());
-    spawners::player::spawn_player(&mut commands, &config);
-    spawners::goal::spawn_goal(&mut commands, &config);
-    spawners::walls::spawn_walls(&mut commands, &config);
-    spawners::blocks::spawn_blocks(&mut commands, &config);
-    spawners::ui::spawn_ui(&mut commands, &asset_server);
+/// Generates a level from the default level's physics and writes it out.
+/// Returns the path it was written to.
+fn generate_level(args: &Args) -> anyhow::Result<String> {
+    let base = json_reader::load_config(&JumparPlugin::default().config_path)?;
+    let params = level_generator::GeneratorParams {
+        seed: args.seed.unwrap_or_else(|| fastrand::u64(..)),
+        difficulty: args
+            .difficulty
+            .unwrap_or(level_generator::GeneratorParams::default().difficulty),
+    };
+    let path = args.out.as_deref().unwrap_or(GENERATED_LEVEL_PATH);
+    json_reader::save_config(&level_generator::generate(&base, &params), path)?;
+    println!(
+        "Generated level {path} with seed {} and difficulty {}",
+        params.seed, params.difficulty
+    );
+    Ok(path.to_string())
 }
@@ -1,53 +1,786 @@
+use crate::events;
+use crate::resources::debug_overlay::{DebugOverlay, LastContacts};
+use crate::resources::editor::EditorState;
+use crate::resources::endless::EndlessRun;
+use crate::resources::json_reader::{read_json, Config, JsonFilePath};
+use crate::resources::key_bindings::{KeyBindings, Rebinding};
+use crate::resources::level_list::LevelList;
+use crate::resources::level_state::{DeathCount, LevelCompleted, LevelTimer, Score};
+use crate::resources::particles::{ParticleEffects, ParticlePool};
+use crate::resources::save_data::{SaveData, SavePath};
+use crate::resources::sounds::SoundEffects;
+use crate::resources::theme::{DefaultThemePath, Theme};
+use crate::spawners::editor::spawn_editor_ui;
+use crate::spawners::menus::{
+    spawn_level_select, spawn_main_menu, spawn_pause_menu, spawn_run_over_screen,
+    spawn_settings_menu,
+};
+use crate::spawners::results::spawn_results_screen;
+use crate::spawners::ui::spawn_height_text;
+use crate::states::GameState;
+use crate::systems::animation_systems::{load_player_animations_system, player_animation_system};
+use crate::systems::block_systems::move_block_system;
+use crate::systems::camera_systems::{
+    camera_follow_system, camera_shake_system, camera_shake_trigger_system,
+};
+use crate::systems::checkpoint_systems::checkpoint_system;
+use crate::systems::collectible_systems::{pickup_system, ride_block_system};
+use crate::systems::debug_systems::{
+    clear_contacts_system, draw_debug_overlay_system, record_contacts_system,
+    toggle_debug_overlay_system,
+};
+use crate::systems::editor_systems::{
+    draw_editor_system, editor_keyboard_system, editor_mouse_system, editor_pan_system,
+    editor_respawn_system, editor_status_system, toggle_editor_system,
+};
+use crate::systems::endless_systems::{
+    despawn_pads_system, endless_camera_system, endless_height_system, fall_out_system,
+    restart_endless_system, spawn_pads_system, start_endless_system, stop_endless_system,
+};
+use crate::systems::goal_systems::{goal_lock_system, goal_system};
+use crate::systems::hazard_systems::{hazard_contact_system, move_saw_system, respawn_system};
+use crate::systems::level_systems::{
+    load_level_system, load_theme_system, mesh_polygon_walls_system, restart_input_system,
+    restart_level_system, texture_sprites_system,
+};
+use crate::systems::menu_systems::{button_color_system, menu_action_system, menu_input_system};
+use crate::systems::parallax_systems::{parallax_system, texture_backgrounds_system};
+use crate::systems::particle_systems::{
+    clear_particles_system, emit_particles_system, spawn_particle_pool, update_particles_system,
+};
 use crate::systems::physics::{
-    apply_gravity, apply_velocity, detect_collision_system, handle_collision_system,
+    apply_gravity, apply_velocity, detect_collision_system, detect_sensor_system,
+    handle_collision_system,
 };
 use crate::systems::player_systems::{
-    player_bounds_system, player_movement_system, player_on_block_moving_system,
+    landing_system, player_movement_system, player_on_block_moving_system,
+};
+use crate::systems::results_systems::{
+    despawn_results_screen, level_complete_system, level_timer_system, results_input_system,
+};
+use crate::systems::save_systems::record_progress_system;
+use crate::systems::settings_systems::{
+    apply_settings_system, cancel_rebinding, fps_visibility_system, rebind_capture_system,
+    settings_button_system, settings_changed, settings_label_system,
+};
+use crate::systems::sound_systems::{
+    level_music_system, load_sounds_system, music_volume_system, play_sounds_system,
 };
-use bevy::prelude::*;
+use crate::systems::ui_systems::{
+    death_text_update_system, hud_theme_system, score_text_update_system, text_update_system,
+    time_text_update_system,
+};
+use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
+use std::path::PathBuf;
+
+/// Sets the game's `FixedUpdate` systems are grouped into.
+///
+/// The sets run in declaration order, so apps can schedule their own systems
+/// with `.before(..)` / `.after(..)` relative to any stage of a tick. They only
+/// run in [`GameState::Playing`].
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JumparSet {
+    /// Forces acting on the player, e.g. gravity.
+    Forces,
+    /// Player input, wall clamping and being carried by blocks.
+    Player,
+    /// Velocity integration.
+    Movement,
+    /// Collision detection and response.
+    Collision,
+    /// Level logic reacting to the resolved positions, e.g. goals and moving blocks.
+    Level,
+}
+
+/// Orders the [`JumparSet`]s. Every plugin calls this, so any subset of them
+/// can be added on its own.
+fn configure_sets(app: &mut App) {
+    app.init_state::<GameState>().configure_sets(
+        FixedUpdate,
+        (
+            JumparSet::Forces,
+            JumparSet::Player,
+            JumparSet::Movement,
+            JumparSet::Collision,
+            JumparSet::Level,
+        )
+            .chain()
+            .run_if(in_state(GameState::Playing)),
+    );
+}
 
-pub struct PhysicsPlugin;
-pub struct PlayerPlugin;
-pub struct GeneralPlugin;
+/// Loads the level JSON, spawns the camera and the level, restarts the level
+/// on `R` or a [`events::RestartLevel`] event and switches levels on
+/// [`events::LoadLevel`].
+pub struct LevelPlugin {
+    /// Path of the first level JSON, relative to the current working directory.
+    pub config_path: String,
+    /// Path of the manifest listing the levels in play order. Without one,
+    /// `config_path` is the only level.
+    pub level_list_path: String,
+    /// Path of the theme JSON for levels that don't name one. Without one,
+    /// such levels use the built-in theme.
+    pub theme_path: Option<String>,
+}
+
+/// Gravity, velocity integration and player/block collisions.
+pub struct PhysicsPlugin {
+    /// Pull the player down when they are not grounded.
+    pub gravity: bool,
+}
+
+/// Keyboard movement and jumping.
+pub struct PlayerPlugin {
+    /// Carry the player along with the block they are standing on.
+    pub ride_blocks: bool,
+}
 
-impl Plugin for GeneralPlugin {
+/// Camera that follows the player within the level bounds, with a dead zone,
+/// smoothing and look-ahead from the level's `camera` params, and shakes on
+/// hard landings and deaths.
+pub struct CameraPlugin;
+
+/// Backgrounds from the level's `backgrounds`, drawn behind the level and
+/// moved with the camera by their parallax factors, optionally repeating and
+/// drifting on their own.
+pub struct ParallaxPlugin;
+
+/// Sprite-sheet animations for the player: idle, run, jump, fall, land and
+/// wall-slide, picked from their state and velocity, with the sprite flipped
+/// to face the way they move. Animations come from the file the level's
+/// `sprites` name.
+pub struct AnimationPlugin;
+
+/// CPU particle bursts: dust when the player jumps and lands, a burst where
+/// they die and confetti at the goal. Particles are pooled, and the effects
+/// are read from a JSON file.
+pub struct ParticlePlugin {
+    /// Path of the effects JSON, relative to the current working directory.
+    pub path: String,
+}
+
+/// Sound effects for jumps, landings, head bumps, pickups, deaths and goals,
+/// and the background music the level's `music` names, at the volumes from
+/// the saved settings, or the default volumes without [`SavePlugin`]. Without
+/// Bevy's audio plugin, e.g. when running headless, the game stays silent.
+pub struct SoundPlugin {
+    /// Path of the sound effects JSON, relative to the current working directory.
+    pub path: String,
+    /// Play sounds and music at all.
+    pub enabled: bool,
+}
+
+/// Bounces moving blocks between the side walls.
+pub struct BlocksPlugin;
+
+/// Spikes, saws, kill zones and lava: touching one kills the player, who then
+/// respawns at the current respawn point.
+pub struct HazardPlugin;
+
+/// Checkpoints that become the respawn point when touched.
+pub struct CheckpointPlugin;
+
+/// Coins and gems that add to the level's score when picked up.
+pub struct CollectiblePlugin;
+
+/// Sends [`events::GoalReached`] when the player touches an unlocked goal.
+pub struct GoalPlugin;
+
+/// Level timer and the results screen shown once a goal is reached.
+pub struct ResultsPlugin;
+
+/// Loads the save file at startup, updates it whenever a level is completed and
+/// applies the saved settings to the window, audio and key bindings.
+#[derive(Default)]
+pub struct SavePlugin {
+    /// Overrides the default save location in the platform data directory.
+    pub path: Option<PathBuf>,
+}
+
+/// Main menu, level select, settings and pause screens. The game starts in
+/// the main menu when this plugin is added.
+pub struct MenuPlugin;
+
+/// Gizmo overlay of colliders, sensors, velocities, the last collision side per
+/// block and the player's grounded state. Toggled with `F3`.
+#[derive(Default)]
+pub struct DebugPlugin {
+    /// Show the overlay from the start.
+    pub enabled: bool,
+}
+
+/// Mouse-driven level editor, toggled with `F2`. Edits the loaded level's
+/// config, respawning the level after each change, and saves it back to the
+/// level file.
+#[derive(Default)]
+pub struct EditorPlugin {
+    /// Start in the editor instead of the main menu.
+    pub start_in_editor: bool,
+}
+
+/// Endless climb started from the main menu: platforms are generated above
+/// the screen as the camera scrolls up, and the run ends when the player falls
+/// off the bottom. The best heights are kept in the save file.
+pub struct EndlessPlugin;
+
+/// Egui panel for live-tuning the level's physics and player parameters and
+/// saving them back to the level file. Toggled with `F1`.
+#[cfg(feature = "inspector")]
+pub struct InspectorPlugin;
+
+/// On-screen HUD text: the FPS counter and level counters.
+pub struct HudPlugin {
+    /// Spawn and update the FPS counter.
+    pub show_fps: bool,
+}
+
+impl Default for LevelPlugin {
+    fn default() -> Self {
+        LevelPlugin {
+            config_path: "assets/config.json".to_string(),
+            level_list_path: "assets/levels.json".to_string(),
+            theme_path: None,
+        }
+    }
+}
+
+impl Default for PhysicsPlugin {
+    fn default() -> Self {
+        PhysicsPlugin { gravity: true }
+    }
+}
+
+impl Default for PlayerPlugin {
+    fn default() -> Self {
+        PlayerPlugin { ride_blocks: true }
+    }
+}
+
+impl Default for HudPlugin {
+    fn default() -> Self {
+        HudPlugin { show_fps: true }
+    }
+}
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        let level_list = LevelList::load_or_single(&self.level_list_path, &self.config_path)
+            .unwrap_or_else(|err| {
+                error!(
+                    "Unable to load level list {}: {err:#}",
+                    self.level_list_path
+                );
+                LevelList::single(&self.config_path)
+            });
+        app.init_state::<GameState>()
+            .insert_resource(JsonFilePath(self.config_path.clone()))
+            .insert_resource(DefaultThemePath(self.theme_path.clone()))
+            .init_resource::<Theme>()
+            .insert_resource(level_list)
+            .add_event::<events::Collision>()
+            .add_event::<events::RestartLevel>()
+            .add_event::<events::LoadLevel>()
+            .init_resource::<DeathCount>()
+            .init_resource::<KeyBindings>()
+            .add_systems(
+                Startup,
+                (read_json, load_theme_system, crate::setup).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    restart_input_system.run_if(in_state(GameState::Playing)),
+                    load_level_system,
+                    load_theme_system.run_if(resource_changed::<Config>),
+                    restart_level_system,
+                    (texture_sprites_system, mesh_polygon_walls_system),
+                )
+                    .chain(),
+            );
+    }
+}
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::Collision>()
+            .add_event::<events::SensorContact>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_velocity.in_set(JumparSet::Movement),
+                    (
+                        detect_collision_system,
+                        handle_collision_system,
+                        detect_sensor_system,
+                    )
+                        .chain()
+                        .in_set(JumparSet::Collision),
+                ),
+            );
+        if self.gravity {
+            app.add_systems(FixedUpdate, apply_gravity.in_set(JumparSet::Forces));
+        }
+    }
+}
+
+impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::Collision>()
+            .add_event::<events::PlayerJumped>()
+            .init_resource::<KeyBindings>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    player_movement_system,
+                    player_on_block_moving_system.run_if(enabled(self.ride_blocks)),
+                )
+                    .chain()
+                    .in_set(JumparSet::Player),
+            )
+            .add_event::<events::PlayerLanded>()
+            .add_systems(FixedUpdate, landing_system.in_set(JumparSet::Level));
+    }
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::PlayerLanded>()
+            .add_event::<events::PlayerDied>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        camera_follow_system.run_if(not(resource_exists::<EndlessRun>)),
+                        camera_shake_trigger_system,
+                    )
+                        .run_if(in_state(GameState::Playing)),
+                    camera_shake_system,
+                )
+                    .chain(),
+            );
+    }
+}
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        // After the camera has moved for the frame, before transforms are
+        // propagated for drawing.
         app.add_systems(
-            FixedUpdate,
+            PostUpdate,
+            (texture_backgrounds_system, parallax_system)
+                .chain()
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<events::PlayerLanded>().add_systems(
+            Update,
             (
-                apply_gravity,
-                player_bounds_system,
-                player_movement_system,
-                player_on_block_moving_system,
-                apply_velocity,
-                detect_collision_system,
-                handle_collision_system,
+                load_player_animations_system.run_if(resource_changed::<Config>),
+                player_animation_system.run_if(in_state(GameState::Playing)),
             )
                 .chain(),
         );
     }
 }
 
-impl Plugin for PlayerPlugin {
+impl Default for ParticlePlugin {
+    fn default() -> Self {
+        ParticlePlugin {
+            path: "assets/particles.json".to_string(),
+        }
+    }
+}
+
+impl Plugin for ParticlePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            FixedUpdate,
-            (player_bounds_system, player_movement_system).chain(),
-        );
+        let effects = ParticleEffects::load(&self.path).unwrap_or_else(|err| {
+            error!("Unable to load particle effects {}: {err:#}", self.path);
+            ParticleEffects::default()
+        });
+        app.insert_resource(effects)
+            .init_resource::<ParticlePool>()
+            .add_event::<events::PlayerJumped>()
+            .add_event::<events::PlayerLanded>()
+            .add_event::<events::PlayerDied>()
+            .add_event::<events::GoalReached>()
+            .add_systems(Startup, spawn_particle_pool)
+            .add_systems(
+                Update,
+                (
+                    emit_particles_system,
+                    update_particles_system
+                        .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Results))),
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), clear_particles_system)
+            .add_systems(OnEnter(GameState::Editor), clear_particles_system);
     }
 }
 
-impl Plugin for PhysicsPlugin {
+impl Default for SoundPlugin {
+    fn default() -> Self {
+        SoundPlugin {
+            path: "assets/sounds.json".to_string(),
+            enabled: true,
+        }
+    }
+}
+
+impl Plugin for SoundPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            FixedUpdate,
+        if !self.enabled {
+            return;
+        }
+        let sounds = SoundEffects::load(&self.path).unwrap_or_else(|err| {
+            error!("Unable to load sound effects {}: {err:#}", self.path);
+            SoundEffects::default()
+        });
+        app.insert_resource(sounds)
+            .add_event::<events::Collision>()
+            .add_event::<events::PlayerJumped>()
+            .add_event::<events::PlayerLanded>()
+            .add_event::<events::CollectiblePickedUp>()
+            .add_event::<events::PlayerDied>()
+            .add_event::<events::GoalReached>()
+            .add_systems(
+                Startup,
+                load_sounds_system.run_if(resource_exists::<Assets<AudioSource>>),
+            )
+            .add_systems(
+                Update,
+                (
+                    play_sounds_system,
+                    level_music_system.run_if(resource_changed::<Config>),
+                    music_volume_system.run_if(resource_exists_and_changed::<SaveData>),
+                )
+                    .chain()
+                    .run_if(resource_exists::<Assets<AudioSource>>),
+            );
+    }
+}
+
+impl Plugin for BlocksPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_systems(FixedUpdate, move_block_system.in_set(JumparSet::Level));
+    }
+}
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::SensorContact>()
+            .add_event::<events::PlayerDied>()
+            .init_resource::<DeathCount>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    move_saw_system,
+                    (hazard_contact_system, respawn_system).chain(),
+                )
+                    .in_set(JumparSet::Level),
+            );
+    }
+}
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::SensorContact>()
+            .add_systems(FixedUpdate, checkpoint_system.in_set(JumparSet::Level));
+    }
+}
+
+impl Plugin for CollectiblePlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::SensorContact>()
+            .add_event::<events::CollectiblePickedUp>()
+            .init_resource::<Score>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    ride_block_system
+                        .in_set(JumparSet::Movement)
+                        .after(apply_velocity),
+                    pickup_system.in_set(JumparSet::Level),
+                ),
+            );
+    }
+}
+
+impl Plugin for GoalPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::SensorContact>()
+            .add_event::<events::GoalReached>()
+            .init_resource::<Score>()
+            .init_resource::<LevelCompleted>()
+            .add_systems(
+                FixedUpdate,
+                (goal_lock_system, goal_system)
+                    .chain()
+                    .in_set(JumparSet::Level)
+                    .after(pickup_system),
+            );
+    }
+}
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::GoalReached>()
+            .add_event::<events::LoadLevel>()
+            .add_event::<events::RestartLevel>()
+            .init_resource::<LevelTimer>()
+            .init_resource::<DeathCount>()
+            .init_resource::<Score>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    level_timer_system.in_set(JumparSet::Level),
+                    level_complete_system
+                        .in_set(JumparSet::Level)
+                        .after(goal_system),
+                ),
+            )
+            .add_systems(OnEnter(GameState::Results), spawn_results_screen)
+            .add_systems(
+                Update,
+                results_input_system.run_if(in_state(GameState::Results)),
+            )
+            .add_systems(OnExit(GameState::Results), despawn_results_screen);
+    }
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        let mut save_path = self.path.clone().map(SavePath).unwrap_or_default();
+        let save_data = SaveData::load(&save_path.0).unwrap_or_else(|err| {
+            error!("Unable to load save {}: {err:#}", save_path.0.display());
+            // Never overwrite the unreadable save with a fresh one; it may be
+            // from a newer version or recoverable by hand.
+            match SaveData::back_up(&save_path.0) {
+                Ok(backup) => warn!("Moved the unreadable save to {}", backup.display()),
+                Err(err) => {
+                    let new_path = save_path.0.with_extension("json.new");
+                    error!(
+                        "Unable to back up the save, saving to {} instead: {err:#}",
+                        new_path.display()
+                    );
+                    save_path.0 = new_path;
+                }
+            }
+            SaveData::default()
+        });
+        configure_sets(app);
+        app.add_event::<events::GoalReached>()
+            .insert_resource(save_data.settings.key_bindings.clone())
+            .insert_resource(save_path)
+            .insert_resource(save_data)
+            .init_resource::<LevelTimer>()
+            .init_resource::<Score>()
+            .add_systems(
+                FixedUpdate,
+                record_progress_system
+                    .in_set(JumparSet::Level)
+                    .after(level_complete_system),
+            )
+            .add_systems(Update, apply_settings_system.run_if(settings_changed));
+    }
+}
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.insert_state(GameState::MainMenu)
+            .enable_state_scoped_entities::<GameState>()
+            .add_event::<events::LoadLevel>()
+            .add_event::<events::RestartLevel>()
+            .init_resource::<SaveData>()
+            .init_resource::<SavePath>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<Rebinding>()
+            .init_resource::<Theme>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnEnter(GameState::LevelSelect), spawn_level_select)
+            .add_systems(OnEnter(GameState::Settings), spawn_settings_menu)
+            .add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    button_color_system,
+                    menu_action_system.before(load_level_system),
+                    menu_input_system,
+                    (
+                        settings_button_system,
+                        rebind_capture_system.before(menu_input_system),
+                        settings_label_system,
+                    )
+                        .run_if(in_state(GameState::Settings)),
+                ),
+            )
+            .add_systems(OnExit(GameState::Settings), cancel_rebinding);
+    }
+}
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.add_event::<events::Collision>()
+            .add_event::<events::RestartLevel>()
+            .insert_resource(DebugOverlay {
+                enabled: self.enabled,
+            })
+            .init_resource::<LastContacts>()
+            .add_systems(FixedUpdate, record_contacts_system.in_set(JumparSet::Level))
+            .add_systems(
+                Update,
+                (
+                    toggle_debug_overlay_system,
+                    clear_contacts_system,
+                    draw_debug_overlay_system.run_if(|overlay: Res<DebugOverlay>| overlay.enabled),
+                ),
+            );
+    }
+}
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        if self.start_in_editor {
+            app.insert_state(GameState::Editor);
+        }
+        app.enable_state_scoped_entities::<GameState>()
+            .add_event::<events::RestartLevel>()
+            .init_resource::<EditorState>()
+            .add_systems(
+                OnEnter(GameState::Editor),
+                (spawn_editor_ui, editor_respawn_system),
+            )
+            .add_systems(OnExit(GameState::Editor), editor_respawn_system)
+            .add_systems(
+                Update,
+                (
+                    toggle_editor_system,
+                    (
+                        editor_pan_system,
+                        editor_mouse_system,
+                        editor_keyboard_system,
+                        editor_respawn_system.run_if(resource_changed::<Config>),
+                        draw_editor_system,
+                        editor_status_system,
+                    )
+                        .chain()
+                        .run_if(in_state(GameState::Editor))
+                        .before(restart_level_system),
+                ),
+            );
+    }
+}
+
+impl Plugin for EndlessPlugin {
+    fn build(&self, app: &mut App) {
+        configure_sets(app);
+        app.enable_state_scoped_entities::<GameState>()
+            .add_event::<events::StartEndless>()
+            .add_event::<events::RestartLevel>()
+            .init_resource::<SaveData>()
+            .init_resource::<SavePath>()
+            .add_systems(OnEnter(GameState::MainMenu), stop_endless_system)
+            .add_systems(OnEnter(GameState::RunOver), spawn_run_over_screen)
+            .add_systems(
+                Update,
+                (
+                    (start_endless_system, restart_endless_system)
+                        .chain()
+                        .after(menu_action_system)
+                        .before(restart_level_system),
+                    spawn_height_text.run_if(resource_added::<EndlessRun>),
+                    (
+                        endless_camera_system,
+                        spawn_pads_system,
+                        despawn_pads_system,
+                        endless_height_system,
+                        fall_out_system,
+                    )
+                        .chain()
+                        .after(restart_level_system)
+                        .before(camera_shake_system)
+                        .run_if(
+                            in_state(GameState::Playing).and_then(resource_exists::<EndlessRun>),
+                        ),
+                ),
+            );
+    }
+}
+
+#[cfg(feature = "inspector")]
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        use crate::resources::inspector::InspectorPanel;
+        use crate::systems::inspector_systems::{
+            inspector_ui_system, sync_player_mass_system, toggle_inspector_system,
+        };
+
+        if !app.is_plugin_added::<bevy_egui::EguiPlugin>() {
+            app.add_plugins(bevy_egui::EguiPlugin);
+        }
+        app.init_resource::<InspectorPanel>().add_systems(
+            Update,
             (
-                apply_gravity,
-                apply_velocity,
-                detect_collision_system,
-                handle_collision_system,
+                toggle_inspector_system,
+                inspector_ui_system,
+                sync_player_mass_system.run_if(resource_exists_and_changed::<Config>),
             )
                 .chain(),
         );
     }
 }
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DeathCount>()
+            .init_resource::<Score>()
+            .init_resource::<LevelTimer>()
+            .init_resource::<Theme>()
+            .add_systems(Startup, crate::spawners::ui::spawn_counters)
+            .add_systems(
+                Update,
+                (
+                    death_text_update_system,
+                    score_text_update_system,
+                    time_text_update_system,
+                    hud_theme_system.run_if(resource_changed::<Theme>),
+                ),
+            );
+        if !self.show_fps {
+            return;
+        }
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        app.init_resource::<SaveData>()
+            .add_systems(Startup, crate::spawners::ui::spawn_ui)
+            .add_systems(
+                Update,
+                (
+                    text_update_system,
+                    fps_visibility_system.run_if(settings_changed),
+                ),
+            );
+    }
+}
+
+/// Run condition for systems toggled by a plugin field.
+fn enabled(flag: bool) -> impl Fn() -> bool + Clone {
+    move || flag
+}
@@ -0,0 +1,74 @@
+use super::json_reader::resolve_path;
+use crate::components::player::AnimationState;
+use anyhow::Context;
+use bevy::prelude::Resource;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+/// One sprite-sheet animation.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Animation {
+    /// Atlas indices shown in order.
+    pub frames: Vec<usize>,
+    pub fps: f32,
+    /// Start over after the last frame instead of holding it.
+    #[serde(default = "looping")]
+    pub looping: bool,
+}
+
+fn looping() -> bool {
+    true
+}
+
+impl Animation {
+    /// Frame shown `elapsed` seconds into the animation.
+    pub fn frame(&self, elapsed: f32) -> usize {
+        let count = self.frames.len().max(1);
+        let frame = (elapsed * self.fps).max(0.0) as usize;
+        if self.looping {
+            frame % count
+        } else {
+            frame.min(count - 1)
+        }
+    }
+
+    /// Whether a non-looping animation has shown its last frame for a full
+    /// frame's time. Looping animations never finish.
+    pub fn finished(&self, elapsed: f32) -> bool {
+        !self.looping && elapsed * self.fps >= self.frames.len() as f32
+    }
+}
+
+/// The player's animations, read from the JSON file the level's sprites name.
+/// States without an animation fall back to a similar one, e.g. wall-slide to
+/// fall, so a sheet only needs an idle animation.
+#[derive(Deserialize, Debug, Clone, Default, Resource)]
+pub struct PlayerAnimations {
+    /// Path the animations were read from.
+    #[serde(skip)]
+    pub path: String,
+    pub animations: HashMap<AnimationState, Animation>,
+}
+
+impl PlayerAnimations {
+    pub fn load(path: &str) -> anyhow::Result<PlayerAnimations> {
+        let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+        let mut animations: PlayerAnimations =
+            serde_json::from_str(&data).context("Unable to parse json")?;
+        animations.path = path.to_string();
+        Ok(animations)
+    }
+
+    /// The animation for `state`, or the closest one there is.
+    pub fn get(&self, state: AnimationState) -> Option<&Animation> {
+        let mut state = Some(state);
+        while let Some(current) = state {
+            if let Some(animation) = self.animations.get(&current) {
+                return Some(animation);
+            }
+            state = current.fallback();
+        }
+        None
+    }
+}
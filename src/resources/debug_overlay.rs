@@ -0,0 +1,13 @@
+use crate::events::CollisionSide;
+use bevy::prelude::{Entity, Resource};
+use std::collections::HashMap;
+
+/// Whether the collider/velocity debug overlay is drawn. Toggled with `F3`.
+#[derive(Resource, Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+/// The side of the most recent collision with each block or wall.
+#[derive(Resource, Default)]
+pub struct LastContacts(pub HashMap<Entity, CollisionSide>);
@@ -0,0 +1,345 @@
+use crate::resources::json_reader::{
+    Block, Checkpoint, Config, Goal, Hazard, HazardKind, Objects, Wall,
+};
+use bevy::prelude::{Rect, Resource, Vec2};
+
+/// Something in the level JSON the editor can select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorItem {
+    Block(usize),
+    /// Index into [`Config::walls`].
+    Wall(usize),
+    /// Index into [`Objects::all_goals`].
+    Goal(usize),
+    PlayerStart,
+    Hazard(usize),
+    Checkpoint(usize),
+}
+
+/// What a number key places at the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    Block,
+    Hazard(HazardKind),
+    Checkpoint,
+    Goal,
+    PlayerStart,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Drag {
+    /// Moves the item; `grab` is the cursor's offset from its bottom-left corner.
+    Move { grab: Vec2 },
+    /// Resizes the item, keeping its bottom-left corner in place.
+    Resize { anchor: Vec2 },
+}
+
+#[derive(Resource)]
+pub struct EditorState {
+    pub selected: Option<EditorItem>,
+    pub drag: Option<Drag>,
+    pub snap: bool,
+    pub grid: f32,
+    /// Result of the last save, shown in the status line.
+    pub status: Option<String>,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        EditorState {
+            selected: None,
+            drag: None,
+            snap: true,
+            grid: 10.0,
+            status: None,
+        }
+    }
+}
+
+impl EditorState {
+    pub fn snap(&self, value: Vec2) -> Vec2 {
+        if self.snap {
+            (value / self.grid).round() * self.grid
+        } else {
+            value
+        }
+    }
+}
+
+/// Every selectable item, topmost first.
+pub fn items(config: &Config) -> Vec<EditorItem> {
+    let objects = &config.objects;
+    let mut items = vec![EditorItem::PlayerStart];
+    items.extend((0..objects.all_goals().count()).map(EditorItem::Goal));
+    items.extend((0..objects.checkpoints.len()).map(EditorItem::Checkpoint));
+    items.extend((0..objects.hazards.len()).map(EditorItem::Hazard));
+    items.extend((0..objects.blocks.len()).map(EditorItem::Block));
+    items.extend((0..config.walls().len()).map(EditorItem::Wall));
+    items
+}
+
+/// The item's extent in world coordinates, as spawned by the `spawners`.
+pub fn rect(config: &Config, item: EditorItem) -> Option<Rect> {
+    let objects = &config.objects;
+    let walls = &config.wall_params;
+    let player_size = objects.player.size;
+    let (center, size) = match item {
+        EditorItem::Block(index) => {
+            let block = objects.blocks.get(index)?;
+            (Vec2::new(block.x, block.y), Vec2::new(block.w, block.h))
+        }
+        EditorItem::Wall(index) => return config.walls().get(index).map(Wall::bounds),
+        EditorItem::Goal(index) => {
+            let goal = objects.all_goals().nth(index)?;
+            (
+                Vec2::new(goal.x, goal.y + walls.bottom_y),
+                Vec2::new(goal.w.unwrap_or(player_size), goal.h.unwrap_or(player_size)),
+            )
+        }
+        EditorItem::PlayerStart => (
+            Vec2::new(objects.player.x, objects.player.y + walls.bottom_y),
+            Vec2::splat(player_size),
+        ),
+        EditorItem::Hazard(index) => {
+            let hazard = objects.hazards.get(index)?;
+            (Vec2::new(hazard.x, hazard.y), Vec2::new(hazard.w, hazard.h))
+        }
+        EditorItem::Checkpoint(index) => {
+            let checkpoint = objects.checkpoints.get(index)?;
+            (
+                Vec2::new(checkpoint.x, checkpoint.y),
+                Vec2::new(player_size / 2.0, player_size * 2.0),
+            )
+        }
+    };
+    Some(Rect::from_center_size(center, size))
+}
+
+/// Moves and resizes `item` to `rect`. Polygon walls are scaled to fit;
+/// checkpoints have a fixed size.
+pub fn set_rect(config: &mut Config, item: EditorItem, rect: Rect) {
+    let bottom_y = config.wall_params.bottom_y;
+    let (center, size) = (rect.center(), rect.size());
+    let objects = &mut config.objects;
+    match item {
+        EditorItem::Block(index) => {
+            let Some(block) = objects.blocks.get_mut(index) else {
+                return;
+            };
+            // Pin the default speed, which depends on the block's height.
+            block.speed = Some(block.speed(index));
+            (block.x, block.y, block.w, block.h) = (center.x, center.y, size.x, size.y);
+        }
+        EditorItem::Wall(index) => {
+            let walls = own_walls(config);
+            if let Some(wall) = walls.get_mut(index) {
+                wall.fit(rect);
+            }
+        }
+        EditorItem::Goal(index) => {
+            let Some(goal) = goal_mut(objects, index) else {
+                return;
+            };
+            goal.x = center.x;
+            goal.y = center.y - bottom_y;
+            goal.w = Some(size.x);
+            goal.h = Some(size.y);
+        }
+        EditorItem::PlayerStart => {
+            objects.player.x = center.x;
+            objects.player.y = center.y - bottom_y;
+            objects.player.size = size.x.min(size.y);
+        }
+        EditorItem::Hazard(index) => {
+            let Some(hazard) = objects.hazards.get_mut(index) else {
+                return;
+            };
+            (hazard.x, hazard.y, hazard.w, hazard.h) = (center.x, center.y, size.x, size.y);
+        }
+        EditorItem::Checkpoint(index) => {
+            let Some(checkpoint) = objects.checkpoints.get_mut(index) else {
+                return;
+            };
+            (checkpoint.x, checkpoint.y) = (center.x, center.y);
+        }
+    }
+}
+
+/// Removes `item` from the level. Returns `false` for items a level can't do
+/// without: the last wall and the player start.
+pub fn remove(config: &mut Config, item: EditorItem) -> bool {
+    if let EditorItem::Wall(index) = item {
+        let walls = own_walls(config);
+        // Without any walls the level would fall back on the default box.
+        if index >= walls.len() || walls.len() == 1 {
+            return false;
+        }
+        walls.remove(index);
+        return true;
+    }
+    let objects = &mut config.objects;
+    match item {
+        EditorItem::Block(index) if index < objects.blocks.len() => {
+            let block = objects.blocks.remove(index);
+            // Collectibles riding the block stay where they are; later blocks shift down.
+            for collectible in objects.collectibles.iter_mut() {
+                match collectible.block {
+                    Some(ridden) if ridden == index => {
+                        collectible.x += block.x;
+                        collectible.y += block.y;
+                        collectible.block = None;
+                    }
+                    Some(ridden) if ridden > index => collectible.block = Some(ridden - 1),
+                    _ => {}
+                }
+            }
+            true
+        }
+        EditorItem::Goal(index) => match (&objects.goal, index) {
+            (Some(_), 0) => {
+                objects.goal = None;
+                true
+            }
+            (Some(_), index) if index <= objects.goals.len() => {
+                objects.goals.remove(index - 1);
+                true
+            }
+            (None, index) if index < objects.goals.len() => {
+                objects.goals.remove(index);
+                true
+            }
+            _ => false,
+        },
+        EditorItem::Hazard(index) if index < objects.hazards.len() => {
+            objects.hazards.remove(index);
+            true
+        }
+        EditorItem::Checkpoint(index) if index < objects.checkpoints.len() => {
+            objects.checkpoints.remove(index);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Adds a new item centered at `position` and returns it.
+pub fn place(config: &mut Config, placement: Placement, position: Vec2) -> EditorItem {
+    let bottom_y = config.wall_params.bottom_y;
+    let player_size = config.objects.player.size;
+    let [pad_w, pad_h] = config.wall_params.pad_size;
+    let objects = &mut config.objects;
+    match placement {
+        Placement::Block => {
+            objects.blocks.push(Block {
+                x: position.x,
+                y: position.y,
+                w: pad_w,
+                h: pad_h,
+                speed: Some(0.0),
+            });
+            EditorItem::Block(objects.blocks.len() - 1)
+        }
+        Placement::Hazard(kind) => {
+            let (w, h, speed) = match kind {
+                HazardKind::Spikes => (60.0, 20.0, 0.0),
+                HazardKind::Saw => (40.0, 40.0, 150.0),
+                HazardKind::KillZone => (100.0, 40.0, 0.0),
+            };
+            objects.hazards.push(Hazard {
+                kind,
+                x: position.x,
+                y: position.y,
+                w,
+                h,
+                speed,
+            });
+            EditorItem::Hazard(objects.hazards.len() - 1)
+        }
+        Placement::Checkpoint => {
+            objects.checkpoints.push(Checkpoint {
+                x: position.x,
+                y: position.y,
+            });
+            EditorItem::Checkpoint(objects.checkpoints.len() - 1)
+        }
+        Placement::Goal => {
+            let goal = Goal {
+                x: position.x,
+                y: position.y - bottom_y,
+                w: Some(player_size),
+                h: Some(player_size),
+                requires_all_collectibles: false,
+            };
+            if objects.goal.is_none() {
+                objects.goal = Some(goal);
+                EditorItem::Goal(0)
+            } else {
+                objects.goals.push(goal);
+                EditorItem::Goal(objects.goals.len())
+            }
+        }
+        Placement::PlayerStart => {
+            objects.player.x = position.x;
+            objects.player.y = position.y - bottom_y;
+            EditorItem::PlayerStart
+        }
+        Placement::Wall => {
+            let size = Vec2::new(100.0, config.wall_params.thickness);
+            let walls = own_walls(config);
+            walls.push(Wall::from_rect(Rect::from_center_size(position, size)));
+            EditorItem::Wall(walls.len() - 1)
+        }
+    }
+}
+
+/// The horizontal speed of a block or saw, the items with motion properties.
+pub fn speed(config: &Config, item: EditorItem) -> Option<f32> {
+    match item {
+        EditorItem::Block(index) => config
+            .objects
+            .blocks
+            .get(index)
+            .map(|block| block.speed(index)),
+        EditorItem::Hazard(index) => config
+            .objects
+            .hazards
+            .get(index)
+            .filter(|hazard| hazard.kind == HazardKind::Saw)
+            .map(|hazard| hazard.speed),
+        _ => None,
+    }
+}
+
+pub fn set_speed(config: &mut Config, item: EditorItem, speed: f32) {
+    match item {
+        EditorItem::Block(index) => {
+            if let Some(block) = config.objects.blocks.get_mut(index) {
+                block.speed = Some(speed);
+            }
+        }
+        EditorItem::Hazard(index) => {
+            if let Some(hazard) = config.objects.hazards.get_mut(index) {
+                hazard.speed = speed;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The level's walls, written out from the default box first if it has none,
+/// so they can be edited.
+fn own_walls(config: &mut Config) -> &mut Vec<Wall> {
+    if config.walls.is_empty() {
+        config.walls = config.walls().into_owned();
+    }
+    &mut config.walls
+}
+
+fn goal_mut(objects: &mut Objects, index: usize) -> Option<&mut Goal> {
+    match (&mut objects.goal, index) {
+        (Some(goal), 0) => Some(goal),
+        (Some(_), index) => objects.goals.get_mut(index - 1),
+        (None, index) => objects.goals.get_mut(index),
+    }
+}
@@ -0,0 +1,68 @@
+use crate::resources::json_reader::Config;
+use crate::resources::level_generator::{GeneratorParams, PlatformGenerator};
+use bevy::prelude::Resource;
+
+/// Climb height at which platforms reach full difficulty.
+const MAX_DIFFICULTY_HEIGHT: f32 = 5000.0;
+const START_DIFFICULTY: f32 = 0.2;
+/// Index of the floor in the walls of [`EndlessRun::level`]; the others are
+/// the side walls.
+pub const FLOOR: usize = 0;
+
+/// State of the current endless mode run. Only present during one.
+#[derive(Resource)]
+pub struct EndlessRun {
+    pub generator: PlatformGenerator,
+    /// Height of the player's center when standing on the floor.
+    pub start_y: f32,
+    /// Highest the player has climbed above `start_y`.
+    pub height: f32,
+    /// Place of the finished run in the high-score table.
+    pub rank: Option<usize>,
+    /// Platforms generated so far. They are spawned straight from the
+    /// generator and never added to the config.
+    pub pads: usize,
+}
+
+impl EndlessRun {
+    /// A run with a new random seed, climbing from the floor of `config`.
+    pub fn new(config: &Config) -> EndlessRun {
+        let params = GeneratorParams {
+            seed: fastrand::u64(..),
+            difficulty: START_DIFFICULTY,
+        };
+        EndlessRun {
+            generator: PlatformGenerator::new(config, &params),
+            start_y: config.objects.player.y + config.wall_params.bottom_y,
+            height: 0.0,
+            rank: None,
+            pads: 0,
+        }
+    }
+
+    /// Platforms get harder the higher the run climbs.
+    pub fn difficulty(&self) -> f32 {
+        let progress = (self.height / MAX_DIFFICULTY_HEIGHT).min(1.0);
+        START_DIFFICULTY + (1.0 - START_DIFFICULTY) * progress
+    }
+
+    /// The level an endless run starts from: `config`'s wall box and physics,
+    /// with a solid floor and nothing but the player in it.
+    pub fn level(config: &Config) -> Config {
+        let mut config = config.clone();
+        config.par_times = None;
+        config.tilemap = None;
+        config.wall_params.lava_floor = false;
+        config.walls = config.wall_params.box_walls(config.canvas.height as f32);
+        let objects = &mut config.objects;
+        objects.blocks.clear();
+        objects.goal = None;
+        objects.goals.clear();
+        objects.hazards.clear();
+        objects.checkpoints.clear();
+        objects.collectibles.clear();
+        objects.player.x = 0.0;
+        objects.player.y = (config.wall_params.thickness + objects.player.size) / 2.0;
+        config
+    }
+}
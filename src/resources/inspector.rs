@@ -0,0 +1,9 @@
+use bevy::prelude::Resource;
+
+/// State of the config inspector panel. Toggled with `F1`.
+#[derive(Resource, Default)]
+pub struct InspectorPanel {
+    pub open: bool,
+    /// Result of the last "save to JSON", shown in the panel.
+    pub status: Option<String>,
+}
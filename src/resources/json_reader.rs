@@ -1,58 +1,503 @@
-use bevy::prelude::{Commands, Res, Resource};
+use crate::resources::tiled;
+use crate::resources::tilemap::Tilemap;
+use anyhow::Context;
+use bevy::prelude::{
+    default, Color, Commands, ImageScaleMode, Rect, Res, Resource, TextureAtlasLayout, UVec2, Vec2,
+};
+use bevy::sprite::{BorderRect, TextureSlicer};
 use relative_path::RelativePath;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::borrow::Cow;
 use std::env::current_dir;
+use std::f32::consts::TAU;
 use std::fs;
+use std::path::PathBuf;
 
 #[derive(Resource)]
 pub struct JsonFilePath(pub String);
 
-#[derive(Deserialize, Debug, Resource)]
+#[derive(Serialize, Deserialize, Debug, Clone, Resource)]
 pub struct Config {
     pub window: Window,
     pub canvas: Canvas,
     pub objects: Objects,
     pub wall_params: WallParams,
+    /// Solid level geometry. Without any, the level is the box of three walls
+    /// `wall_params` describes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub walls: Vec<Wall>,
     pub physics: Physics,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub par_times: Option<ParTimes>,
+    #[serde(default, skip_serializing_if = "CameraParams::is_default")]
+    pub camera: CameraParams,
+    #[serde(default, skip_serializing_if = "Sprites::is_empty")]
+    pub sprites: Sprites,
+    /// Parallax layers drawn behind the level, furthest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub backgrounds: Vec<Background>,
+    /// Path of the theme JSON, relative to the current working directory.
+    /// Defaults to the game's theme.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Looping background music, relative to the `assets` directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub music: Option<String>,
+    /// Expanded into `walls` and `objects` by [`load_config`], and kept so
+    /// [`save_config`] won't overwrite it with the expanded level.
+    #[serde(default, skip_serializing)]
+    pub tilemap: Option<Tilemap>,
 }
 
-#[derive(Deserialize, Debug)]
+/// Medal thresholds in seconds; `gold` is the level's par time.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ParTimes {
+    pub gold: f32,
+    pub silver: f32,
+    pub bronze: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Medal {
+    Gold,
+    Silver,
+    Bronze,
+}
+
+impl ParTimes {
+    pub fn medal(&self, seconds: f32) -> Option<Medal> {
+        if seconds <= self.gold {
+            Some(Medal::Gold)
+        } else if seconds <= self.silver {
+            Some(Medal::Silver)
+        } else if seconds <= self.bronze {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Physics {
     pub gravity: f32,
 }
 
-#[derive(Deserialize, Debug)]
+/// How the camera follows the player.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct CameraParams {
+    /// Width and height of the box around the screen center the player can
+    /// move in without the camera following.
+    pub dead_zone: [f32; 2],
+    /// How quickly the camera catches up, per second. `0.0` snaps to the target.
+    pub smoothing: f32,
+    /// How far the camera leads the player in the direction they are running.
+    pub look_ahead: f32,
+    /// Landing faster than this shakes the screen.
+    pub hard_landing_speed: f32,
+    /// Largest shake offset, in pixels.
+    pub shake_amplitude: f32,
+    /// Seconds a full-strength shake takes to settle.
+    pub shake_duration: f32,
+}
+
+impl Default for CameraParams {
+    fn default() -> Self {
+        CameraParams {
+            dead_zone: [80.0, 60.0],
+            smoothing: 6.0,
+            look_ahead: 60.0,
+            hard_landing_speed: 400.0,
+            shake_amplitude: 10.0,
+            shake_duration: 0.4,
+        }
+    }
+}
+
+impl CameraParams {
+    fn is_default(&self) -> bool {
+        *self == CameraParams::default()
+    }
+}
+
+/// Textures for the level's entities. Entities without one are drawn in
+/// flat colors.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Sprites {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player: Option<SpriteTexture>,
+    /// Path of the JSON file with the player's animations, relative to the
+    /// current working directory. Its frames index into `player`'s atlas.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_animations: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block: Option<SpriteTexture>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<SpriteTexture>,
+    /// Polygon walls stretch the whole texture over their bounds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wall: Option<SpriteTexture>,
+}
+
+impl Sprites {
+    fn is_empty(&self) -> bool {
+        *self == Sprites::default()
+    }
+}
+
+/// An image, or one cell of a texture atlas, drawn at the entity's size.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpriteTexture {
+    /// Path relative to the `assets` directory.
+    pub texture: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atlas: Option<Atlas>,
+    #[serde(default, skip_serializing_if = "ScaleMode::is_stretch")]
+    pub scale_mode: ScaleMode,
+    /// Multiplied with the texture; white leaves it unchanged.
+    #[serde(default = "white", skip_serializing_if = "is_white")]
+    pub tint: [f32; 3],
+}
+
+impl SpriteTexture {
+    pub fn tint(&self) -> Color {
+        Color::srgb_from_array(self.tint)
+    }
+}
+
+/// A texture split into a grid of equally sized cells.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Atlas {
+    /// Size of a cell in pixels.
+    pub tile_size: [u32; 2],
+    pub columns: u32,
+    pub rows: u32,
+    /// Gap between neighbouring cells.
+    #[serde(default)]
+    pub padding: [u32; 2],
+    /// Position of the top-left cell.
+    #[serde(default)]
+    pub offset: [u32; 2],
+    /// Cell shown, counting along rows from the top-left.
+    #[serde(default)]
+    pub index: usize,
+}
+
+impl Atlas {
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            UVec2::from(self.tile_size),
+            self.columns,
+            self.rows,
+            Some(UVec2::from(self.padding)),
+            Some(UVec2::from(self.offset)),
+        )
+    }
+}
+
+/// How a texture covers an entity of a different size than the texture.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaleMode {
+    /// Scaled to the entity's size.
+    #[default]
+    Stretch,
+    /// Corners keep their size, edges and center stretch; for blocks of any
+    /// size. `border` is the corner size in texture pixels.
+    NineSlice { border: f32 },
+    /// Repeated at its own size.
+    Tile,
+}
+
+impl ScaleMode {
+    fn is_stretch(&self) -> bool {
+        *self == ScaleMode::Stretch
+    }
+
+    /// What Bevy needs to draw the mode, if anything beyond the default.
+    pub fn image_scale_mode(&self) -> Option<ImageScaleMode> {
+        match *self {
+            ScaleMode::Stretch => None,
+            ScaleMode::NineSlice { border } => Some(ImageScaleMode::Sliced(TextureSlicer {
+                border: BorderRect::square(border),
+                ..default()
+            })),
+            ScaleMode::Tile => Some(ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 1.0,
+            }),
+        }
+    }
+}
+
+/// An image behind the level that moves slower or faster than the level as
+/// the camera follows the player, so it looks further away or closer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Background {
+    /// Path relative to the `assets` directory.
+    pub texture: String,
+    /// How far the layer moves with the level on each axis: 1.0 moves with
+    /// the walls and blocks, 0.0 stays fixed on screen.
+    #[serde(default = "parallax")]
+    pub parallax: [f32; 2],
+    /// Repeat the image to fill the view horizontally.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub repeat_x: bool,
+    /// Repeat the image to fill the view vertically.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub repeat_y: bool,
+    /// Drift in pixels per second, e.g. for clouds.
+    #[serde(default)]
+    pub scroll: [f32; 2],
+    /// Where the image's center is, relative to the center of the level,
+    /// while the camera looks at the center of the level.
+    #[serde(default)]
+    pub offset: [f32; 2],
+    /// Drawn size relative to the image's size in pixels.
+    #[serde(default = "scale")]
+    pub scale: f32,
+    /// Multiplied with the texture; white leaves it unchanged.
+    #[serde(default = "white", skip_serializing_if = "is_white")]
+    pub tint: [f32; 3],
+}
+
+impl Config {
+    /// The level's walls: `walls`, or the default box if there are none.
+    pub fn walls(&self) -> Cow<'_, [Wall]> {
+        if self.walls.is_empty() {
+            Cow::Owned(self.wall_params.box_walls(self.canvas.height as f32))
+        } else {
+            Cow::Borrowed(&self.walls)
+        }
+    }
+
+    /// Area covered by the walls, which the camera stays inside.
+    pub fn bounds(&self) -> Rect {
+        let walls = &self.wall_params;
+        self.walls()
+            .iter()
+            .map(Wall::bounds)
+            .reduce(|bounds, wall| bounds.union(wall))
+            // A lava floor is as wide as the box.
+            .map_or_else(
+                || walls.box_bounds(self.canvas.height as f32),
+                |bounds| {
+                    if walls.lava_floor {
+                        bounds.union(walls.floor())
+                    } else {
+                        bounds
+                    }
+                },
+            )
+    }
+}
+
+/// Looks and extent of the level's walls. Moving blocks and saws bounce
+/// between `left_x` and `right_x`, and the player start and goal heights are
+/// measured from `bottom_y`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WallParams {
     pub thickness: f32,
+    /// Wall color, unless the theme sets one.
     pub color: [f32; 3],
     pub left_x: f32,
     pub right_x: f32,
     pub bottom_y: f32,
+    /// Size of blocks placed in the editor; generated platforms are this tall.
     pub pad_size: [f32; 2],
+    /// Block color, unless the theme sets one.
     pub pad_color: [f32; 3],
+    /// Replaces the solid bottom wall with lava that kills the player.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub lava_floor: bool,
+}
+
+impl WallParams {
+    /// The bottom wall, spanning the box including the side walls.
+    pub fn floor(&self) -> Rect {
+        Rect::new(
+            self.left_x - self.thickness / 2.0,
+            self.bottom_y - self.thickness / 2.0,
+            self.right_x + self.thickness / 2.0,
+            self.bottom_y + self.thickness / 2.0,
+        )
+    }
+
+    /// The left, bottom and right walls, with the side walls rising `height`
+    /// above `bottom_y`.
+    fn box_bounds(&self, height: f32) -> Rect {
+        let floor = self.floor();
+        Rect::new(floor.min.x, floor.min.y, floor.max.x, floor.max.y + height)
+    }
+
+    /// The default walls: a floor, unless it is lava, and two side walls
+    /// standing on it, `height` tall. Levels pass their canvas height.
+    pub fn box_walls(&self, height: f32) -> Vec<Wall> {
+        let bounds = self.box_bounds(height);
+        let side = |x: f32| {
+            Wall::from_rect(Rect::new(
+                x - self.thickness / 2.0,
+                bounds.min.y,
+                x + self.thickness / 2.0,
+                bounds.max.y,
+            ))
+        };
+        let floor = (!self.lava_floor).then(|| Wall::from_rect(self.floor()));
+        floor
+            .into_iter()
+            .chain([side(self.left_x), side(self.right_x)])
+            .collect()
+    }
 }
 
-#[derive(Deserialize, Debug)]
+/// A solid piece of level geometry, e.g. a floor, ceiling, ledge or slope.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Wall {
+    /// Centered at `x`, `y`, like blocks.
+    Rect { x: f32, y: f32, w: f32, h: f32 },
+    /// A convex polygon; concave shapes need splitting into several walls.
+    /// Levels with any other polygon fail to load.
+    Polygon { points: Vec<[f32; 2]> },
+}
+
+impl Wall {
+    pub fn from_rect(rect: Rect) -> Wall {
+        let (center, size) = (rect.center(), rect.size());
+        Wall::Rect {
+            x: center.x,
+            y: center.y,
+            w: size.x,
+            h: size.y,
+        }
+    }
+
+    pub fn bounds(&self) -> Rect {
+        match self {
+            Wall::Rect { x, y, w, h } => {
+                Rect::from_center_size(Vec2::new(*x, *y), Vec2::new(*w, *h))
+            }
+            Wall::Polygon { points } => points
+                .iter()
+                .map(|&point| Rect::from_center_size(Vec2::from(point), Vec2::ZERO))
+                .reduce(|bounds, point| bounds.union(point))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Checks that a polygon is one collisions and drawing can handle: at
+    /// least three distinct corners, in order around a convex shape.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let Wall::Polygon { points } = self else {
+            return Ok(());
+        };
+        if points.len() < 3 {
+            anyhow::bail!("A polygon needs at least 3 points, not {}", points.len());
+        }
+        if points.iter().flatten().any(|value| !value.is_finite()) {
+            anyhow::bail!("Polygon points must be finite numbers");
+        }
+        let points = self.points();
+        let edges: Vec<Vec2> = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .map(|(a, b)| *b - *a)
+            .collect();
+        if edges.contains(&Vec2::ZERO) {
+            anyhow::bail!("Polygon has the same point twice in a row");
+        }
+        // Counterclockwise and convex: every corner turns left, and the turns
+        // add up to a single lap, which rules out self-intersecting stars.
+        let corners = edges.iter().zip(edges.iter().cycle().skip(1));
+        if corners.clone().any(|(a, b)| a.perp_dot(*b) < 0.0) {
+            anyhow::bail!("Polygon is concave; split it into convex walls");
+        }
+        let turned: f32 = corners.map(|(a, b)| a.angle_between(*b)).sum();
+        if (turned - TAU).abs() > 0.01 {
+            anyhow::bail!("Polygon crosses itself");
+        }
+        Ok(())
+    }
+
+    /// Corners in world coordinates, counterclockwise.
+    pub fn points(&self) -> Vec<Vec2> {
+        match self {
+            Wall::Rect { .. } => {
+                let Rect { min, max } = self.bounds();
+                vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+            }
+            Wall::Polygon { points } => {
+                let mut points: Vec<Vec2> = points.iter().copied().map(Vec2::from).collect();
+                // Shoelace formula: a negative area means clockwise.
+                let area: f32 = points
+                    .iter()
+                    .zip(points.iter().cycle().skip(1))
+                    .map(|(a, b)| a.perp_dot(*b))
+                    .sum();
+                if area < 0.0 {
+                    points.reverse();
+                }
+                points
+            }
+        }
+    }
+
+    /// Moves and scales the wall to fill `rect`.
+    pub fn fit(&mut self, rect: Rect) {
+        let bounds = self.bounds();
+        match self {
+            Wall::Rect { .. } => *self = Wall::from_rect(rect),
+            Wall::Polygon { points } => {
+                let scale = rect.size() / bounds.size().max(Vec2::splat(f32::EPSILON));
+                for point in points.iter_mut() {
+                    *point = (rect.min + (Vec2::from(*point) - bounds.min) * scale).into();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Window {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Canvas {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Objects {
+    #[serde(default)]
     pub blocks: Vec<Block>,
     pub player: Player,
-    pub goal: Goal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<Goal>,
+    /// Further goals; reaching any one of them completes the level.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hazards: Vec<Hazard>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<Checkpoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collectibles: Vec<Collectible>,
+}
+
+impl Objects {
+    pub fn all_goals(&self) -> impl Iterator<Item = &Goal> {
+        self.goal.iter().chain(self.goals.iter())
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Player {
     pub x: f32,
     pub y: f32,
@@ -63,24 +508,258 @@ pub struct Player {
     pub start_y: f32,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Block {
     pub x: f32,
     pub y: f32,
     pub w: f32,
     pub h: f32,
+    /// Horizontal speed; negative starts the block moving left. Defaults to a
+    /// speed derived from the block's height, alternating direction by index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+}
+
+impl Block {
+    pub fn speed(&self, index: usize) -> f32 {
+        self.speed.unwrap_or_else(|| {
+            let direction = if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+            (self.y + 300.0 + index as f32 * 25.0) * direction
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HazardKind {
+    Spikes,
+    /// Moves horizontally at `speed`, bouncing off the side walls.
+    Saw,
+    /// Invisible area, e.g. a pit the player must not fall into.
+    KillZone,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    #[serde(default)]
+    pub speed: f32,
+}
+
+/// Absolute position, like blocks and hazards.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Checkpoint {
+    pub x: f32,
+    pub y: f32,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectibleKind {
+    Coin,
+    Gem,
+}
+
+impl CollectibleKind {
+    pub fn points(&self) -> u32 {
+        match self {
+            CollectibleKind::Coin => 1,
+            CollectibleKind::Gem => 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Collectible {
+    pub kind: CollectibleKind,
+    /// Absolute position, or the offset from the block's center when `block` is set.
+    pub x: f32,
+    pub y: f32,
+    /// Index into `blocks` of a block the collectible rides along with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<usize>,
+}
+
+/// `y` is measured from the bottom wall, like the player's.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Goal {
     pub x: f32,
     pub y: f32,
+    /// Defaults to the player size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub w: Option<f32>,
+    /// Defaults to the player size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub h: Option<f32>,
+    /// The goal stays locked until every collectible has been picked up.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub requires_all_collectibles: bool,
 }
 
-pub fn read_json(path: Res<JsonFilePath>, mut commands: Commands) {
+/// Resolves `path` against the current working directory.
+pub fn resolve_path(path: &str) -> PathBuf {
     let root = current_dir().unwrap();
-    let path: std::path::PathBuf = RelativePath::new(&path.0).to_path(root);
-    let data = fs::read_to_string(path.into_os_string()).expect("Unable to read file");
-    let config: Config = serde_json::from_str(&data).expect("Unable to parse json");
+    RelativePath::new(path).to_path(root)
+}
+
+/// Loads a level JSON, or a Tiled JSON map if `path` ends in `.tmj`. Tiled's
+/// XML format (`.tmx`) is deliberately not read; maps need exporting as JSON.
+pub fn load_config(path: &str) -> anyhow::Result<Config> {
+    if path.ends_with(".tmx") {
+        anyhow::bail!(
+            "Tiled XML maps (.tmx) are not supported, use File > Export As in Tiled to save the map as JSON (.tmj)"
+        );
+    }
+    let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+    let config = if is_tiled_map(path) {
+        tiled::load_map(path, &data)?
+    } else {
+        let mut config: Config = serde_json::from_str(&data).context("Unable to parse json")?;
+        if let Some(tilemap) = config.tilemap.clone() {
+            tilemap.apply(&mut config).context("Invalid tilemap")?;
+        }
+        config
+    };
+    for (index, wall) in config.walls.iter().enumerate() {
+        wall.validate()
+            .with_context(|| format!("Invalid wall {index}"))?;
+    }
+    Ok(config)
+}
+
+/// Writes `config` to `path` in the same layout as the hand-written level files.
+pub fn save_config(config: &Config, path: &str) -> anyhow::Result<()> {
+    if is_tiled_map(path) {
+        anyhow::bail!("Tiled maps can only be edited in Tiled");
+    }
+    if config.tilemap.is_some() {
+        anyhow::bail!("Levels laid out with a tilemap can only be edited in their tilemap");
+    }
+    let mut data = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut data, formatter);
+    config.serialize(&mut serializer)?;
+    data.push(b'\n');
+    fs::write(resolve_path(path), data).context("Unable to write file")?;
+    Ok(())
+}
+
+pub(crate) fn is_tiled_map(path: &str) -> bool {
+    path.ends_with(".tmj")
+}
+
+fn white() -> [f32; 3] {
+    [1.0; 3]
+}
+
+fn is_white(color: &[f32; 3]) -> bool {
+    *color == white()
+}
+
+fn parallax() -> [f32; 2] {
+    [1.0; 2]
+}
+
+fn scale() -> f32 {
+    1.0
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+pub fn read_json(path: Res<JsonFilePath>, mut commands: Commands) {
+    let config = load_config(&path.0).expect("Unable to load level");
     commands.insert_resource(config);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[[f32; 2]]) -> Wall {
+        Wall::Polygon {
+            points: points.to_vec(),
+        }
+    }
+
+    #[test]
+    fn convex_polygons_are_valid_either_way_round() {
+        let square = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        assert!(polygon(&square).validate().is_ok());
+        let mut clockwise = square;
+        clockwise.reverse();
+        assert!(polygon(&clockwise).validate().is_ok());
+        assert!(polygon(&[[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]])
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn degenerate_polygons_are_rejected() {
+        assert!(polygon(&[[0.0, 0.0], [10.0, 0.0]]).validate().is_err());
+        assert!(
+            polygon(&[[0.0, 0.0], [10.0, 0.0], [10.0, 0.0], [0.0, 10.0]])
+                .validate()
+                .is_err()
+        );
+        assert!(polygon(&[[0.0, 0.0], [f32::NAN, 0.0], [0.0, 10.0]])
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn concave_polygons_are_rejected() {
+        let arrow = [[0.0, 0.0], [10.0, 5.0], [0.0, 10.0], [3.0, 5.0]];
+        assert!(polygon(&arrow).validate().is_err());
+    }
+
+    #[test]
+    fn self_intersecting_polygons_are_rejected() {
+        let star: Vec<[f32; 2]> = (0..5)
+            .map(|i| {
+                let angle = i as f32 * 2.0 * TAU / 5.0;
+                [angle.cos() * 10.0, angle.sin() * 10.0]
+            })
+            .collect();
+        assert!(polygon(&star).validate().is_err());
+    }
+
+    #[test]
+    fn box_side_walls_stand_on_the_floor() {
+        let config = load_config("assets/config.json").unwrap();
+        let params = &config.wall_params;
+        let height = config.canvas.height as f32;
+        let walls = params.box_walls(height);
+        assert_eq!(walls.len(), 3);
+        let floor = walls[0].bounds();
+        assert_eq!(floor, params.floor());
+        for (side, x) in walls[1..].iter().zip([params.left_x, params.right_x]) {
+            let side = side.bounds();
+            assert_eq!(side.center().x, x);
+            assert_eq!(side.min.y, floor.min.y);
+            assert_eq!(side.max.y, floor.max.y + height);
+        }
+        assert_eq!(
+            config.bounds(),
+            Rect::new(floor.min.x, floor.min.y, floor.max.x, floor.max.y + height)
+        );
+    }
+
+    #[test]
+    fn shipped_levels_load() {
+        for path in [
+            "assets/config.json",
+            "assets/levels/level2.json",
+            "assets/levels/level3.json",
+            "assets/levels/level4.json",
+            "assets/levels/level5.tmj",
+        ] {
+            load_config(path).unwrap_or_else(|err| panic!("{path}: {err:#}"));
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::{ButtonInput, KeyCode, Resource};
+use serde::{Deserialize, Serialize};
+
+/// Game inputs that can be bound to a key.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Restart,
+    Pause,
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::Restart,
+        Action::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Jump => "Jump",
+            Action::Restart => "Restart",
+            Action::Pause => "Pause",
+        }
+    }
+}
+
+/// Maps each [`Action`] to a key. Gameplay systems query actions through this
+/// instead of reading `KeyCode`s directly, so bindings can be changed in the settings.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub jump: KeyCode,
+    pub restart: KeyCode,
+    pub pause: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            move_left: KeyCode::KeyA,
+            move_right: KeyCode::KeyD,
+            jump: KeyCode::Space,
+            restart: KeyCode::KeyR,
+            pause: KeyCode::Escape,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::MoveLeft => self.move_left,
+            Action::MoveRight => self.move_right,
+            Action::Jump => self.jump,
+            Action::Restart => self.restart,
+            Action::Pause => self.pause,
+        }
+    }
+
+    /// Binds `key` to `action`. An action already bound to `key` takes over
+    /// `action`'s previous key, so no key triggers two actions.
+    pub fn set(&mut self, action: Action, key: KeyCode) {
+        let previous = self.key(action);
+        if let Some(other) = Action::ALL
+            .into_iter()
+            .find(|&other| other != action && self.key(other) == key)
+        {
+            *self.slot(other) = previous;
+        }
+        *self.slot(action) = key;
+    }
+
+    fn slot(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::MoveLeft => &mut self.move_left,
+            Action::MoveRight => &mut self.move_right,
+            Action::Jump => &mut self.jump,
+            Action::Restart => &mut self.restart,
+            Action::Pause => &mut self.pause,
+        }
+    }
+
+    pub fn pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        input.pressed(self.key(action))
+    }
+
+    pub fn just_pressed(&self, action: Action, input: &ButtonInput<KeyCode>) -> bool {
+        input.just_pressed(self.key(action))
+    }
+}
+
+/// The action waiting for a key press in the settings menu, if any.
+#[derive(Resource, Default)]
+pub struct Rebinding(pub Option<Action>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binding_a_taken_key_swaps_the_keys() {
+        let mut bindings = KeyBindings::default();
+        bindings.set(Action::Jump, KeyCode::KeyA);
+        assert_eq!(bindings.jump, KeyCode::KeyA);
+        assert_eq!(bindings.move_left, KeyCode::Space);
+    }
+
+    #[test]
+    fn binding_a_free_key_changes_only_that_action() {
+        let mut bindings = KeyBindings::default();
+        bindings.set(Action::Jump, KeyCode::KeyW);
+        assert_eq!(bindings.jump, KeyCode::KeyW);
+        let others = Action::ALL
+            .into_iter()
+            .filter(|&action| action != Action::Jump);
+        for action in others {
+            assert_eq!(bindings.key(action), KeyBindings::default().key(action));
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use crate::resources::json_reader::{Block, Config, Goal};
+
+/// Placements tried for each platform before giving up.
+const MAX_ATTEMPTS: u32 = 50;
+
+/// Inputs of [`generate`]. The same seed and difficulty always produce the
+/// same level.
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorParams {
+    pub seed: u64,
+    /// From 0.0 (wide, static platforms with easy jumps) to 1.0 (narrow,
+    /// often moving platforms at the edge of the player's reach).
+    pub difficulty: f32,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        GeneratorParams {
+            seed: 0,
+            difficulty: 0.5,
+        }
+    }
+}
+
+/// How far a jump carries the player, from the level's physics.
+#[derive(Debug, Clone, Copy)]
+pub struct JumpReach {
+    /// Gravity acceleration, as applied by `apply_gravity`.
+    pub gravity: f32,
+    pub jump_force: f32,
+    pub speed: f32,
+}
+
+impl JumpReach {
+    pub fn new(config: &Config) -> JumpReach {
+        let player = &config.objects.player;
+        JumpReach {
+            gravity: config.physics.gravity * player.mass,
+            jump_force: player.jump_force,
+            speed: player.speed,
+        }
+    }
+
+    /// Highest the player's feet rise above the take-off surface.
+    pub fn max_height(&self) -> f32 {
+        self.jump_force * self.jump_force / (2.0 * self.gravity)
+    }
+
+    /// Horizontal distance covered by the time the player comes back down to
+    /// `height` above the take-off surface, or `None` if it is out of reach.
+    pub fn distance(&self, height: f32) -> Option<f32> {
+        let discriminant = self.jump_force * self.jump_force - 2.0 * self.gravity * height;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let airtime = (self.jump_force + discriminant.sqrt()) / self.gravity;
+        Some(self.speed * airtime)
+    }
+}
+
+/// Generates platforms one above the other, each reachable from the one
+/// before.
+///
+/// Every platform's top is at most a jump above the previous one. A static
+/// platform also starts within a jump's horizontal reach of the previous
+/// platform if that one is static too. Moving platforms bounce across the
+/// whole level, so they come within reach of any platform sooner or later and
+/// only the height counts. Every platform leaves room for the player to stand
+/// on the platforms below it.
+pub struct PlatformGenerator {
+    rng: fastrand::Rng,
+    /// Can be raised between platforms, e.g. as a climb gets higher.
+    pub difficulty: f32,
+    /// No platform's top goes above this.
+    pub ceiling: f32,
+    reach: JumpReach,
+    player_size: f32,
+    /// Platform height, from the level's `pad_size`.
+    pad_height: f32,
+    inner_left: f32,
+    inner_right: f32,
+    /// The surface the next jump takes off from, with the horizontal extent of
+    /// the platform if it is static. The floor and moving platforms let the
+    /// player take off from anywhere.
+    surface: f32,
+    take_off: Option<(f32, f32)>,
+    previous_moving: bool,
+    /// Left, right and top of the static platforms near `surface`.
+    platforms: Vec<(f32, f32, f32)>,
+}
+
+impl PlatformGenerator {
+    /// Starts from the floor of `config`'s walls, which the player must be
+    /// able to stand on.
+    pub fn new(config: &Config, params: &GeneratorParams) -> PlatformGenerator {
+        let walls = &config.wall_params;
+        PlatformGenerator {
+            rng: fastrand::Rng::with_seed(params.seed),
+            difficulty: params.difficulty.clamp(0.0, 1.0),
+            ceiling: f32::INFINITY,
+            reach: JumpReach::new(config),
+            player_size: config.objects.player.size,
+            pad_height: walls.pad_size[1],
+            inner_left: walls.left_x + walls.thickness / 2.0,
+            inner_right: walls.right_x - walls.thickness / 2.0,
+            surface: walls.bottom_y + walls.thickness / 2.0,
+            take_off: None,
+            previous_moving: false,
+            platforms: Vec::new(),
+        }
+    }
+
+    /// Top of the last platform, or of the floor.
+    pub fn surface(&self) -> f32 {
+        self.surface
+    }
+
+    fn lerp(&self, easy: f32, hard: f32) -> f32 {
+        easy + (hard - easy) * self.difficulty
+    }
+
+    /// Highest the next platform may rise above the current surface.
+    fn max_rise(&self) -> f32 {
+        // Share of the jump's reach the generator uses.
+        self.reach.max_height() * self.lerp(0.75, 0.95)
+    }
+
+    /// Center of a goal the player can reach from the last platform.
+    pub fn goal_position(&self) -> (f32, f32) {
+        let x = match self.take_off {
+            Some((left, right)) => (left + right) / 2.0,
+            None if self.previous_moving => (self.inner_left + self.inner_right) / 2.0,
+            None => self.inner_right - self.player_size,
+        };
+        let y = if self.previous_moving {
+            // A goal can't ride a moving block, so hover it a short hop above its path.
+            self.surface + self.max_rise() / 2.0
+        } else {
+            self.surface + self.player_size / 2.0
+        };
+        (x, y)
+    }
+}
+
+impl Iterator for PlatformGenerator {
+    type Item = Block;
+
+    /// The next platform, or `None` once the ceiling is reached or no
+    /// platform fits.
+    fn next(&mut self) -> Option<Block> {
+        let height = self.pad_height;
+        let player_size = self.player_size;
+        let max_rise = self.max_rise();
+        // Vertical room for the player between a platform and any above it.
+        let clearance = player_size + height;
+
+        for attempt in 0..MAX_ATTEMPTS {
+            // Moving platforms fit anywhere, so fall back on one when static
+            // ones keep failing.
+            let moving = clearance <= max_rise
+                && (attempt + 1 == MAX_ATTEMPTS || self.rng.f32() < self.lerp(0.0, 0.6));
+            let mut rise = max_rise * (0.5 + 0.5 * self.rng.f32());
+            if moving || self.previous_moving {
+                rise = rise.max(clearance);
+            }
+            let top = self.surface + rise;
+            if top > self.ceiling {
+                return None;
+            }
+            let width = self.lerp(160.0, 60.0) * (0.8 + 0.4 * self.rng.f32());
+
+            let x = match self.take_off {
+                Some((left, right)) if !moving => {
+                    // Gap between the take-off edge and the landing edge,
+                    // leaving room for the player's own width.
+                    let max_gap = self.reach.distance(rise).unwrap_or(0.0) * self.lerp(0.75, 0.95)
+                        - player_size;
+                    let gap = player_size
+                        + (max_gap - player_size).max(0.0) * self.lerp(0.2, 1.0) * self.rng.f32();
+                    if self.rng.bool() {
+                        right + gap + width / 2.0
+                    } else {
+                        left - gap - width / 2.0
+                    }
+                }
+                _ => {
+                    let room = self.inner_right - self.inner_left - width;
+                    self.inner_left + width / 2.0 + self.rng.f32() * room
+                }
+            };
+            let (left, right) = (x - width / 2.0, x + width / 2.0);
+            let blocked = left < self.inner_left
+                || right > self.inner_right
+                || !moving
+                    && self.platforms.iter().any(|&(l, r, platform_top)| {
+                        top - platform_top < clearance
+                            && left - player_size < r
+                            && right + player_size > l
+                    });
+            if blocked {
+                continue;
+            }
+
+            let speed = if moving {
+                let speed = self.lerp(80.0, 250.0) * (0.8 + 0.4 * self.rng.f32());
+                if self.rng.bool() {
+                    speed
+                } else {
+                    -speed
+                }
+            } else {
+                0.0
+            };
+            // Platforms more than `clearance` below can't get in the way any more.
+            self.platforms
+                .retain(|&(_, _, platform_top)| top - platform_top < clearance);
+            if !moving {
+                self.platforms.push((left, right, top));
+            }
+            self.take_off = (!moving).then_some((left, right));
+            self.previous_moving = moving;
+            self.surface = top;
+            return Some(Block {
+                x,
+                y: top - height / 2.0,
+                w: width,
+                h: height,
+                speed: Some(speed),
+            });
+        }
+        None
+    }
+}
+
+/// Replaces the objects and walls of `base` with a generated climb of platforms
+/// from the floor of its wall box to a goal near the top of the canvas. The
+/// floor is solid even if `base` has a lava floor.
+pub fn generate(base: &Config, params: &GeneratorParams) -> Config {
+    let walls = &base.wall_params;
+    let mut generator = PlatformGenerator::new(base, params);
+    generator.ceiling = walls.bottom_y + base.canvas.height as f32 - base.objects.player.size * 2.0;
+
+    let mut config = base.clone();
+    config.par_times = None;
+    config.tilemap = None;
+    // The first platform is only reachable from the floor.
+    config.wall_params.lava_floor = false;
+    // Platforms are laid out inside the wall box.
+    config.walls.clear();
+    let objects = &mut config.objects;
+    objects.blocks = generator.by_ref().collect();
+    objects.goals.clear();
+    objects.hazards.clear();
+    objects.checkpoints.clear();
+    objects.collectibles.clear();
+
+    let (goal_x, goal_y) = generator.goal_position();
+    objects.goal = Some(Goal {
+        x: goal_x,
+        y: goal_y - walls.bottom_y,
+        w: None,
+        h: None,
+        requires_all_collectibles: false,
+    });
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::json_reader::load_config;
+
+    const SEEDS: [u64; 8] = [0, 1, 2, 3, 42, 1234, 99_999, u64::MAX];
+    const DIFFICULTIES: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+    /// Left, right and top of a platform.
+    fn edges(block: &Block) -> (f32, f32, f32) {
+        (
+            block.x - block.w / 2.0,
+            block.x + block.w / 2.0,
+            block.y + block.h / 2.0,
+        )
+    }
+
+    /// Checks every platform's rise, and the gap from a static platform to the
+    /// next static one, against the player's jump.
+    fn assert_reachable(blocks: &[Block], reach: JumpReach, floor: f32, context: &str) {
+        assert!(!blocks.is_empty(), "{context}: no platforms");
+        let mut surface = floor;
+        let mut previous: Option<&Block> = None;
+        for (index, block) in blocks.iter().enumerate() {
+            let (left, right, top) = edges(block);
+            let rise = top - surface;
+            assert!(
+                rise <= reach.max_height(),
+                "{context}, block {index}: rise {rise}"
+            );
+
+            let from_static = previous.filter(|previous| previous.speed == Some(0.0));
+            if let (Some(previous), Some(0.0)) = (from_static, block.speed) {
+                let (previous_left, previous_right, _) = edges(previous);
+                let gap = (left - previous_right).max(previous_left - right).max(0.0);
+                let distance = reach.distance(rise).unwrap();
+                assert!(
+                    gap <= distance,
+                    "{context}, block {index}: gap {gap} > {distance}"
+                );
+            }
+            surface = top;
+            previous = Some(block);
+        }
+    }
+
+    #[test]
+    fn every_platform_is_reachable_from_the_one_before() {
+        let base = load_config("assets/config.json").unwrap();
+        let walls = &base.wall_params;
+        let floor = walls.bottom_y + walls.thickness / 2.0;
+        for seed in SEEDS {
+            for difficulty in DIFFICULTIES {
+                let config = generate(&base, &GeneratorParams { seed, difficulty });
+                let context = format!("seed {seed}, difficulty {difficulty}");
+                assert_reachable(
+                    &config.objects.blocks,
+                    JumpReach::new(&base),
+                    floor,
+                    &context,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn endless_climbs_stay_reachable() {
+        let base = load_config("assets/config.json").unwrap();
+        let walls = &base.wall_params;
+        let floor = walls.bottom_y + walls.thickness / 2.0;
+        for seed in SEEDS {
+            let params = GeneratorParams {
+                seed,
+                difficulty: 0.0,
+            };
+            let mut generator = PlatformGenerator::new(&base, &params);
+            let blocks: Vec<Block> = (0..200)
+                .map_while(|index| {
+                    generator.difficulty = index as f32 / 200.0;
+                    generator.next()
+                })
+                .collect();
+            assert_eq!(blocks.len(), 200, "seed {seed}");
+            assert_reachable(
+                &blocks,
+                JumpReach::new(&base),
+                floor,
+                &format!("seed {seed}"),
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_the_same_level() {
+        let base = load_config("assets/config.json").unwrap();
+        let params = GeneratorParams {
+            seed: 7,
+            difficulty: 0.6,
+        };
+        let first = generate(&base, &params);
+        let second = generate(&base, &params);
+        assert_eq!(
+            format!("{:?}", first.objects.blocks),
+            format!("{:?}", second.objects.blocks)
+        );
+    }
+
+    #[test]
+    fn lava_floor_is_made_solid() {
+        let mut base = load_config("assets/config.json").unwrap();
+        base.wall_params.lava_floor = true;
+        let config = generate(&base, &GeneratorParams::default());
+        assert!(!config.wall_params.lava_floor);
+    }
+}
@@ -0,0 +1,51 @@
+use super::json_reader::resolve_path;
+use anyhow::Context;
+use bevy::prelude::Resource;
+use serde::Deserialize;
+use std::fs;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LevelEntry {
+    pub name: String,
+    /// Path of the level JSON, relative to the current working directory.
+    pub path: String,
+}
+
+/// The levels in play order, read from the level manifest.
+#[derive(Deserialize, Debug, Resource)]
+pub struct LevelList {
+    pub levels: Vec<LevelEntry>,
+}
+
+impl LevelList {
+    /// Reads the manifest at `path`, or falls back to a list holding only
+    /// `config_path` when there is no manifest.
+    pub fn load_or_single(path: &str, config_path: &str) -> anyhow::Result<LevelList> {
+        let path = resolve_path(path);
+        if !path.exists() {
+            return Ok(LevelList::single(config_path));
+        }
+        let data = fs::read_to_string(path).context("Unable to read file")?;
+        let list = serde_json::from_str(&data).context("Unable to parse json")?;
+        Ok(list)
+    }
+
+    /// A list holding only `config_path`.
+    pub fn single(config_path: &str) -> LevelList {
+        LevelList {
+            levels: vec![LevelEntry {
+                name: config_path.to_string(),
+                path: config_path.to_string(),
+            }],
+        }
+    }
+
+    pub fn index_of(&self, path: &str) -> Option<usize> {
+        self.levels.iter().position(|level| level.path == path)
+    }
+
+    pub fn next_after(&self, path: &str) -> Option<&LevelEntry> {
+        self.index_of(path)
+            .and_then(|index| self.levels.get(index + 1))
+    }
+}
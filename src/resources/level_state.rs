@@ -0,0 +1,37 @@
+use bevy::prelude::{Resource, Vec2};
+use std::time::Duration;
+
+/// Where the player reappears after dying.
+#[derive(Resource)]
+pub struct RespawnPoint(pub Vec2);
+
+#[derive(Resource, Default)]
+pub struct DeathCount(pub u32);
+
+/// Set once a goal has been reached, so `GoalReached` is only sent once per attempt.
+#[derive(Resource, Default)]
+pub struct LevelCompleted(pub bool);
+
+#[derive(Resource, Default)]
+pub struct Score {
+    pub points: u32,
+    pub collected: usize,
+    /// Number of collectibles in the level.
+    pub total: usize,
+}
+
+/// Time spent in the current attempt. Runs from spawn until a goal is reached.
+#[derive(Resource)]
+pub struct LevelTimer {
+    pub elapsed: Duration,
+    pub running: bool,
+}
+
+impl Default for LevelTimer {
+    fn default() -> Self {
+        LevelTimer {
+            elapsed: Duration::ZERO,
+            running: true,
+        }
+    }
+}
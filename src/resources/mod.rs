@@ -1 +1,17 @@
+pub mod animation;
+pub mod debug_overlay;
+pub mod editor;
+pub mod endless;
+#[cfg(feature = "inspector")]
+pub mod inspector;
 pub mod json_reader;
+pub mod key_bindings;
+pub mod level_generator;
+pub mod level_list;
+pub mod level_state;
+pub mod particles;
+pub mod save_data;
+pub mod sounds;
+pub mod theme;
+pub mod tiled;
+pub mod tilemap;
@@ -0,0 +1,67 @@
+use super::json_reader::resolve_path;
+use anyhow::Context;
+use bevy::prelude::{Entity, Resource};
+use serde::Deserialize;
+use std::fs;
+
+/// A burst of particles. Every range is `[min, max]`, picked from per particle.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Emitter {
+    pub count: usize,
+    /// Seconds a particle lives.
+    pub lifetime: [f32; 2],
+    pub speed: [f32; 2],
+    /// Direction in degrees, counterclockwise from the right.
+    pub angle: f32,
+    /// How far directions may stray either side of `angle`, in degrees.
+    pub spread: f32,
+    /// Downward acceleration in pixels per second squared.
+    pub gravity: f32,
+    /// Share of its velocity a particle loses per second.
+    pub drag: f32,
+    /// Size at birth and at death.
+    pub size: [f32; 2],
+    /// Each particle takes one of these, with alpha.
+    pub colors: Vec<[f32; 4]>,
+    /// Particles start scattered this far from the emitter, horizontally and
+    /// vertically.
+    pub radius: [f32; 2],
+    /// Fade out over the particle's lifetime.
+    pub fade: bool,
+}
+
+/// The game's particle effects, read from a JSON file when the game starts.
+/// Effects left out of the file emit nothing.
+#[derive(Deserialize, Debug, Clone, Default, Resource)]
+pub struct ParticleEffects {
+    /// Particles allocated up front. Bursts beyond what is free are cut short.
+    pub pool_size: usize,
+    /// At the player's feet when they jump.
+    #[serde(default)]
+    pub jump: Emitter,
+    /// At the player's feet when they land.
+    #[serde(default)]
+    pub land: Emitter,
+    /// Where the player died.
+    #[serde(default)]
+    pub death: Emitter,
+    /// At the goal the player reached.
+    #[serde(default)]
+    pub goal: Emitter,
+}
+
+impl ParticleEffects {
+    pub fn load(path: &str) -> anyhow::Result<ParticleEffects> {
+        let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+        let effects = serde_json::from_str(&data).context("Unable to parse json")?;
+        Ok(effects)
+    }
+}
+
+/// Hidden particle entities waiting to be emitted, so bursts reuse entities
+/// instead of spawning new ones.
+#[derive(Resource, Default)]
+pub struct ParticlePool {
+    pub free: Vec<Entity>,
+}
@@ -0,0 +1,240 @@
+use super::key_bindings::KeyBindings;
+use anyhow::{bail, Context};
+use bevy::prelude::{warn, Resource};
+use bevy::window::WindowMode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Version written by this build. Bump it together with a new entry in
+/// [`MIGRATIONS`] whenever a change can't be handled by `#[serde(default)]`.
+pub const SAVE_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n]` upgrades a version `n` save to version `n + 1`.
+/// Version 0 is a save written before the `version` field existed.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0];
+
+#[derive(Resource)]
+pub struct SavePath(pub PathBuf);
+
+impl Default for SavePath {
+    /// `jumpar/save.json` in the platform data directory. Without one, falls
+    /// back to `.jumpar/save.json` in the home directory, and failing that to
+    /// the working directory.
+    fn default() -> Self {
+        let dir = match (dirs::data_dir(), dirs::home_dir()) {
+            (Some(data), _) => data.join("jumpar"),
+            (None, Some(home)) => {
+                warn!("No data directory found, saving under the home directory");
+                home.join(".jumpar")
+            }
+            (None, None) => {
+                warn!("No data or home directory found, saving in the working directory");
+                PathBuf::from(".jumpar")
+            }
+        };
+        SavePath(dir.join("save.json"))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Resource)]
+pub struct SaveData {
+    pub version: u32,
+    /// Paths of the levels that can be played, in the order they were unlocked.
+    #[serde(default)]
+    pub unlocked_levels: Vec<String>,
+    /// Best results, keyed by level path.
+    #[serde(default)]
+    pub records: BTreeMap<String, LevelRecord>,
+    #[serde(default)]
+    pub settings: Settings,
+    /// Best endless mode heights, highest first.
+    #[serde(default)]
+    pub endless_scores: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct LevelRecord {
+    pub best_time: Option<f32>,
+    #[serde(default)]
+    pub best_collected: usize,
+}
+
+/// Number of endless mode heights kept in the high-score table.
+pub const ENDLESS_SCORES: usize = 10;
+
+/// Window sizes offered in the settings menu.
+pub const RESOLUTIONS: [[u32; 2]; 4] = [[800, 600], [1280, 720], [1600, 900], [1920, 1080]];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub show_fps: bool,
+    pub window_mode: WindowMode,
+    /// Window size when `window_mode` is windowed.
+    pub resolution: [u32; 2],
+    pub vsync: bool,
+    /// Volumes between 0.0 and 1.0; music and SFX are scaled by `master_volume`.
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            show_fps: true,
+            window_mode: WindowMode::Windowed,
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+            master_volume: 1.0,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        SaveData {
+            version: SAVE_VERSION,
+            unlocked_levels: Vec::new(),
+            records: BTreeMap::new(),
+            settings: Settings::default(),
+            endless_scores: Vec::new(),
+        }
+    }
+}
+
+impl SaveData {
+    /// Reads the save at `path`, migrating older versions. A missing file is a fresh save.
+    pub fn load(path: &Path) -> anyhow::Result<SaveData> {
+        if !path.exists() {
+            return Ok(SaveData::default());
+        }
+        let data = fs::read_to_string(path).context("Unable to read file")?;
+        let mut value: Value = serde_json::from_str(&data).context("Unable to parse json")?;
+        migrate(&mut value)?;
+        let save = serde_json::from_value(value).context("Unable to parse save data")?;
+        Ok(save)
+    }
+
+    /// Writes to a temporary file next to `path` and renames it over the old
+    /// save, so a crash mid-write never leaves a truncated save behind.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Unable to create save directory")?;
+        }
+        let data = serde_json::to_string_pretty(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, data).context("Unable to write file")?;
+        fs::rename(&tmp_path, path).context("Unable to replace save file")?;
+        Ok(())
+    }
+
+    /// Moves an unreadable save at `path` out of the way, to `save.json.bak`
+    /// or the first of `save.json.1.bak`, `save.json.2.bak`, ... that is
+    /// free, and returns where it went.
+    pub fn back_up(path: &Path) -> anyhow::Result<PathBuf> {
+        let backup = (0..)
+            .map(|n| match n {
+                0 => path.with_extension("json.bak"),
+                n => path.with_extension(format!("json.{n}.bak")),
+            })
+            .find(|backup| !backup.exists())
+            .unwrap();
+        fs::rename(path, &backup).context("Unable to rename save file")?;
+        Ok(backup)
+    }
+
+    pub fn is_unlocked(&self, level_path: &str) -> bool {
+        self.unlocked_levels.iter().any(|path| path == level_path)
+    }
+
+    pub fn unlock(&mut self, level_path: &str) {
+        if !self.is_unlocked(level_path) {
+            self.unlocked_levels.push(level_path.to_string());
+        }
+    }
+
+    /// Keeps the better of the stored and the given results.
+    pub fn record(&mut self, level_path: &str, time: f32, collected: usize) {
+        let record = self.records.entry(level_path.to_string()).or_default();
+        if record.best_time.is_none_or(|best| time < best) {
+            record.best_time = Some(time);
+        }
+        record.best_collected = record.best_collected.max(collected);
+    }
+
+    /// Adds an endless run's height to the high-score table. Returns its rank,
+    /// or `None` if it didn't make the table.
+    pub fn record_endless(&mut self, height: u32) -> Option<usize> {
+        let rank = self
+            .endless_scores
+            .partition_point(|&score| score >= height);
+        if rank >= ENDLESS_SCORES {
+            return None;
+        }
+        self.endless_scores.insert(rank, height);
+        self.endless_scores.truncate(ENDLESS_SCORES);
+        Some(rank)
+    }
+}
+
+fn migrate(value: &mut Value) -> anyhow::Result<()> {
+    if !value.is_object() {
+        bail!("Save data is not a JSON object");
+    }
+    let mut version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if version > SAVE_VERSION {
+        bail!("Save version {version} is newer than supported version {SAVE_VERSION}");
+    }
+    while version < SAVE_VERSION {
+        MIGRATIONS[version as usize](value);
+        version += 1;
+        value["version"] = version.into();
+    }
+    Ok(())
+}
+
+/// Saves without a `version` field predate versioning; the fields they lack
+/// fall back to their defaults, so there is nothing to rewrite.
+fn migrate_v0(_value: &mut Value) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_save_migrates() {
+        let path = std::env::temp_dir().join(format!("jumpar-v0-{}.json", std::process::id()));
+        let v0 = r#"{
+            "unlocked_levels": ["assets/levels/level1.json"],
+            "records": { "assets/levels/level1.json": { "best_time": 12.5 } },
+            "settings": { "show_fps": false }
+        }"#;
+        fs::write(&path, v0).unwrap();
+        let save = SaveData::load(&path);
+        fs::remove_file(&path).unwrap();
+        let save = save.unwrap();
+
+        assert_eq!(save.version, SAVE_VERSION);
+        assert!(save.is_unlocked("assets/levels/level1.json"));
+        let record = &save.records["assets/levels/level1.json"];
+        assert_eq!(record.best_time, Some(12.5));
+        assert_eq!(record.best_collected, 0);
+        assert!(!save.settings.show_fps);
+        assert_eq!(save.settings.music_volume, Settings::default().music_volume);
+        assert!(save.endless_scores.is_empty());
+    }
+
+    #[test]
+    fn newer_save_is_rejected() {
+        let mut value = serde_json::json!({ "version": SAVE_VERSION + 1 });
+        assert!(migrate(&mut value).is_err());
+    }
+}
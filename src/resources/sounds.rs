@@ -0,0 +1,38 @@
+use super::json_reader::resolve_path;
+use anyhow::Context;
+use bevy::prelude::{AudioSource, Handle, Resource};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+/// A gameplay moment with a sound effect.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Sound {
+    Jump,
+    Land,
+    /// The player hit the underside of a block or wall.
+    Bump,
+    Pickup,
+    Death,
+    Goal,
+}
+
+/// The game's sound effects, read from a JSON file when the game starts.
+/// Sounds left out of the file are not played.
+#[derive(Deserialize, Debug, Clone, Default, Resource)]
+pub struct SoundEffects {
+    /// Audio files relative to the `assets` directory.
+    pub sounds: HashMap<Sound, String>,
+    /// Filled in from `sounds` once the asset server is up.
+    #[serde(skip)]
+    pub handles: HashMap<Sound, Handle<AudioSource>>,
+}
+
+impl SoundEffects {
+    pub fn load(path: &str) -> anyhow::Result<SoundEffects> {
+        let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+        let sounds = serde_json::from_str(&data).context("Unable to parse json")?;
+        Ok(sounds)
+    }
+}
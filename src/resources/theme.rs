@@ -0,0 +1,193 @@
+use super::json_reader::{resolve_path, Config, HazardKind};
+use anyhow::Context;
+use bevy::prelude::{AssetServer, Color, Interaction, Res, Resource, TextStyle};
+use serde::Deserialize;
+use std::fs;
+
+/// Colors and HUD style the level is drawn with, read from a theme JSON.
+/// A level picks its theme with `theme`; levels without one use the theme
+/// the game was started with, or the built-in look if there is none.
+#[derive(Deserialize, Debug, Clone, PartialEq, Resource)]
+#[serde(default)]
+pub struct Theme {
+    /// Path the theme was read from; `None` for the built-in theme.
+    #[serde(skip)]
+    pub path: Option<String>,
+    pub background: [f32; 3],
+    /// Overrides the level's `wall_params.color`.
+    pub wall: Option<[f32; 3]>,
+    /// Blocks. Overrides the level's `wall_params.pad_color`.
+    pub pad: Option<[f32; 3]>,
+    pub player: [f32; 3],
+    pub goal: [f32; 3],
+    /// A goal waiting for every collectible to be picked up.
+    pub goal_locked: [f32; 3],
+    pub checkpoint: [f32; 3],
+    /// The checkpoint the player respawns at.
+    pub checkpoint_active: [f32; 3],
+    pub spikes: [f32; 3],
+    pub saw: [f32; 3],
+    pub lava: [f32; 3],
+    pub coin: [f32; 3],
+    pub gem: [f32; 3],
+    pub hud: HudTheme,
+}
+
+/// Text of the HUD, menus, results screen and editor, and the menus' colors.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HudTheme {
+    /// Path relative to the `assets` directory.
+    pub font: String,
+    pub color: [f32; 3],
+    /// Counter values and titles.
+    pub highlight: [f32; 3],
+    /// Background of the results screen, editor help and the menus shown
+    /// over a level, with alpha.
+    pub panel: [f32; 4],
+    /// Background of the full-screen menus.
+    pub menu: [f32; 3],
+    pub button: [f32; 3],
+    pub button_hover: [f32; 3],
+    pub button_pressed: [f32; 3],
+    /// Levels in the level select that aren't unlocked yet.
+    pub button_locked: [f32; 3],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            path: None,
+            background: [0.169, 0.173, 0.184],
+            wall: None,
+            pad: None,
+            player: [0.5, 0.5, 1.0],
+            goal: [1.0, 0.5, 0.5],
+            goal_locked: [0.5, 0.35, 0.35],
+            checkpoint: [0.3, 0.5, 0.3],
+            checkpoint_active: [0.3, 1.0, 0.4],
+            spikes: [0.9, 0.1, 0.1],
+            saw: [0.9, 0.5, 0.1],
+            lava: [1.0, 0.35, 0.0],
+            coin: [1.0, 0.84, 0.0],
+            gem: [0.2, 0.9, 1.0],
+            hud: HudTheme::default(),
+        }
+    }
+}
+
+impl Default for HudTheme {
+    fn default() -> Self {
+        HudTheme {
+            font: "fonts/FiraSans-Bold.ttf".to_string(),
+            color: [1.0, 1.0, 1.0],
+            highlight: [1.0, 0.843, 0.0],
+            panel: [0.0, 0.0, 0.0, 0.7],
+            menu: [0.05, 0.05, 0.08],
+            button: [0.2, 0.2, 0.25],
+            button_hover: [0.3, 0.3, 0.4],
+            button_pressed: [0.4, 0.5, 0.4],
+            button_locked: [0.12, 0.12, 0.12],
+        }
+    }
+}
+
+/// Darkens a textured goal while it is locked.
+const LOCKED_TINT: [f32; 3] = [0.4, 0.4, 0.4];
+
+impl Theme {
+    pub fn load(path: &str) -> anyhow::Result<Theme> {
+        let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+        let mut theme: Theme = serde_json::from_str(&data).context("Unable to parse json")?;
+        theme.path = Some(path.to_string());
+        Ok(theme)
+    }
+
+    /// Color of rectangular walls: the tint of the level's wall texture, or
+    /// the theme's or level's wall color.
+    pub fn wall_color(&self, config: &Config) -> Color {
+        match &config.sprites.wall {
+            Some(texture) => texture.tint(),
+            None => Color::srgb_from_array(self.wall.unwrap_or(config.wall_params.color)),
+        }
+    }
+
+    pub fn pad_color(&self, config: &Config) -> Color {
+        match &config.sprites.block {
+            Some(texture) => texture.tint(),
+            None => Color::srgb_from_array(self.pad.unwrap_or(config.wall_params.pad_color)),
+        }
+    }
+
+    pub fn player_color(&self, config: &Config) -> Color {
+        match &config.sprites.player {
+            Some(texture) => texture.tint(),
+            None => Color::srgb_from_array(self.player),
+        }
+    }
+
+    pub fn goal_color(&self, config: &Config, locked: bool) -> Color {
+        Color::srgb_from_array(match (&config.sprites.goal, locked) {
+            (Some(_), true) => LOCKED_TINT,
+            (Some(texture), false) => texture.tint,
+            (None, true) => self.goal_locked,
+            (None, false) => self.goal,
+        })
+    }
+
+    pub fn checkpoint_color(&self, active: bool) -> Color {
+        Color::srgb_from_array(if active {
+            self.checkpoint_active
+        } else {
+            self.checkpoint
+        })
+    }
+
+    /// Kill zones are invisible and have no color.
+    pub fn hazard_color(&self, kind: HazardKind) -> Color {
+        match kind {
+            HazardKind::Spikes => Color::srgb_from_array(self.spikes),
+            HazardKind::Saw => Color::srgb_from_array(self.saw),
+            HazardKind::KillZone => Color::NONE,
+        }
+    }
+
+    /// Plain HUD text in the theme's font.
+    pub fn text_style(&self, asset_server: &Res<AssetServer>, font_size: f32) -> TextStyle {
+        TextStyle {
+            font: asset_server.load(&self.hud.font),
+            font_size,
+            color: Color::srgb_from_array(self.hud.color),
+        }
+    }
+
+    pub fn highlight(&self) -> Color {
+        Color::srgb_from_array(self.hud.highlight)
+    }
+
+    pub fn panel(&self) -> Color {
+        let [red, green, blue, alpha] = self.hud.panel;
+        Color::srgba(red, green, blue, alpha)
+    }
+
+    pub fn menu(&self) -> Color {
+        Color::srgb_from_array(self.hud.menu)
+    }
+
+    pub fn button_color(&self, interaction: Interaction) -> Color {
+        Color::srgb_from_array(match interaction {
+            Interaction::Pressed => self.hud.button_pressed,
+            Interaction::Hovered => self.hud.button_hover,
+            Interaction::None => self.hud.button,
+        })
+    }
+
+    pub fn button_locked(&self) -> Color {
+        Color::srgb_from_array(self.hud.button_locked)
+    }
+}
+
+/// Theme for levels that don't name their own, relative to the current
+/// working directory.
+#[derive(Resource)]
+pub struct DefaultThemePath(pub Option<String>);
@@ -0,0 +1,529 @@
+use crate::resources::json_reader::{
+    is_tiled_map, load_config, resolve_path, Block, Checkpoint, Collectible, CollectibleKind,
+    Config, Goal, Hazard, HazardKind, Wall,
+};
+use crate::resources::tilemap::merge;
+use anyhow::{bail, Context};
+use bevy::prelude::{Rect, Vec2};
+use relative_path::{RelativePath, RelativePathBuf};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+use std::fs;
+
+/// Level whose window, physics, player and camera settings a map uses unless
+/// it names another one in its `template` property.
+const DEFAULT_TEMPLATE: &str = "assets/config.json";
+
+/// The parts of a Tiled JSON map (`.tmj`) a level is built from.
+#[derive(Deserialize, Debug)]
+struct Map {
+    width: usize,
+    height: usize,
+    tilewidth: f32,
+    tileheight: f32,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<Layer>,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Layer {
+    Tilelayer {
+        data: TileData,
+        #[serde(default)]
+        properties: Vec<Property>,
+    },
+    Objectgroup {
+        objects: Vec<Object>,
+    },
+    Group {
+        layers: Vec<Layer>,
+    },
+    /// Image layers are only decoration.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum TileData {
+    Csv(Vec<u32>),
+    /// Base64, optionally compressed; not supported.
+    Encoded(IgnoredAny),
+}
+
+#[derive(Deserialize, Debug)]
+struct Object {
+    #[serde(default)]
+    name: String,
+    /// Called "class" from Tiled 1.9 on.
+    #[serde(default, rename = "type", alias = "class")]
+    kind: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    /// Set on tile objects, which are positioned by their bottom-left corner.
+    #[serde(default)]
+    gid: Option<u32>,
+    /// Relative to `x`, `y`.
+    #[serde(default)]
+    polygon: Option<Vec<Point>>,
+    #[serde(default)]
+    properties: Vec<Property>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize, Debug)]
+struct Property {
+    name: String,
+    value: serde_json::Value,
+}
+
+/// The custom property `name`, if set and of type `T`.
+fn property<T: DeserializeOwned>(properties: &[Property], name: &str) -> Option<T> {
+    let property = properties.iter().find(|property| property.name == name)?;
+    serde_json::from_value(property.value.clone()).ok()
+}
+
+/// Builds a level from a Tiled JSON map. Only the JSON format is read: maps
+/// saved as XML (`.tmx`) have to be exported as `.tmj` first, and tile layer
+/// data must use CSV rather than Base64 encoding.
+///
+/// Every non-empty tile of a tile layer is solid, unless the layer's `solid`
+/// property is `false`; neighbouring tiles merge into larger walls. Objects
+/// are recognized by their type (or class, or failing that, name):
+///
+/// - `block`: a block, static unless it has a `speed` property.
+/// - `wall`: a solid rectangle or polygon.
+/// - `spikes`, `saw`, `kill_zone`: hazards; saws move at their `speed`.
+/// - `goal`: a goal, optionally with `requires_all_collectibles`.
+/// - `start`: the player start, standing on the object's bottom edge.
+/// - `checkpoint`: a checkpoint standing on the object's bottom edge.
+/// - `coin`, `gem`: collectibles.
+///
+/// Everything else, including the physics and the player, comes from the
+/// level named by the map's `template` property.
+pub fn load_map(path: &str, data: &str) -> anyhow::Result<Config> {
+    load_map_visiting(path, data, &mut Vec::new())
+}
+
+/// [`load_map`], refusing templates already in `visited`, the chain of maps
+/// that led to this one.
+fn load_map_visiting(
+    path: &str,
+    data: &str,
+    visited: &mut Vec<RelativePathBuf>,
+) -> anyhow::Result<Config> {
+    visited.push(RelativePath::new(path).normalize());
+    let map: Map = serde_json::from_str(data).context("Unable to parse Tiled map")?;
+    if map.infinite {
+        bail!("Infinite Tiled maps are not supported");
+    }
+    let template =
+        property(&map.properties, "template").unwrap_or_else(|| DEFAULT_TEMPLATE.to_string());
+    if visited.contains(&RelativePath::new(&template).normalize()) {
+        bail!("Template {template} is the map itself or one of its own templates");
+    }
+    let mut config = load_template(&template, visited)
+        .with_context(|| format!("Unable to load template {template}"))?;
+
+    let tile = Vec2::new(map.tilewidth, map.tileheight);
+    let size = Vec2::new(map.width as f32, map.height as f32) * tile;
+    // The map is centered on the origin, with its outermost tiles as the
+    // wall box that blocks and saws bounce inside.
+    let origin = -size / 2.0;
+    let walls = &mut config.wall_params;
+    walls.thickness = tile.x;
+    walls.left_x = origin.x + tile.x / 2.0;
+    walls.right_x = -walls.left_x;
+    walls.bottom_y = origin.y + tile.y / 2.0;
+    walls.lava_floor = false;
+    config.tilemap = None;
+    config.walls.clear();
+    let objects = &mut config.objects;
+    objects.blocks.clear();
+    objects.goal = None;
+    objects.goals.clear();
+    objects.hazards.clear();
+    objects.checkpoints.clear();
+    objects.collectibles.clear();
+
+    add_layers(&mut config, &map, &map.layers)?;
+    Ok(config)
+}
+
+/// Loads a map's template, which may be a map with a template of its own.
+fn load_template(path: &str, visited: &mut Vec<RelativePathBuf>) -> anyhow::Result<Config> {
+    if !is_tiled_map(path) {
+        return load_config(path);
+    }
+    let data = fs::read_to_string(resolve_path(path)).context("Unable to read file")?;
+    load_map_visiting(path, &data, visited)
+}
+
+/// Adds the walls and objects of `layers` to `config`, in order.
+fn add_layers(config: &mut Config, map: &Map, layers: &[Layer]) -> anyhow::Result<()> {
+    let tile = Vec2::new(map.tilewidth, map.tileheight);
+    let size = Vec2::new(map.width as f32, map.height as f32) * tile;
+    for layer in layers {
+        match layer {
+            Layer::Tilelayer { data, properties } => {
+                if !property(properties, "solid").unwrap_or(true) {
+                    continue;
+                }
+                let TileData::Csv(data) = data else {
+                    bail!("Tile layers must use CSV encoding");
+                };
+                if map.width == 0 || data.len() != map.width * map.height {
+                    bail!(
+                        "Tile layer has {} tiles, expected {} by {}",
+                        data.len(),
+                        map.width,
+                        map.height
+                    );
+                }
+                let grid: Vec<Vec<bool>> = data
+                    .chunks(map.width)
+                    .map(|row| row.iter().map(|&gid| gid != 0).collect())
+                    .collect();
+                let rects = merge(&grid, &true, true)
+                    .into_iter()
+                    .map(|area| Wall::from_rect(area.rect(-size / 2.0, tile, map.height)));
+                config.walls.extend(rects);
+            }
+            Layer::Objectgroup { objects } => {
+                for object in objects {
+                    add_object(config, object, size)
+                        .with_context(|| format!("Invalid object {:?}", object.name))?;
+                }
+            }
+            Layer::Group { layers } => add_layers(config, map, layers)?,
+            Layer::Other => {}
+        }
+    }
+    Ok(())
+}
+
+/// Adds the level object `object` stands for to `config`. `size` is the map's
+/// size in pixels.
+fn add_object(config: &mut Config, object: &Object, size: Vec2) -> anyhow::Result<()> {
+    // Tiled measures y downwards from the map's top-left corner.
+    let to_world = |x: f32, y: f32| Vec2::new(x - size.x / 2.0, size.y / 2.0 - y);
+    let top = if object.gid.is_some() {
+        object.y - object.height
+    } else {
+        object.y
+    };
+    let rect = Rect::from_corners(
+        to_world(object.x, top),
+        to_world(object.x + object.width, top + object.height),
+    );
+    let (center, bottom) = (rect.center(), rect.min.y);
+    let bottom_y = config.wall_params.bottom_y;
+    let player_size = config.objects.player.size;
+    let properties = &object.properties;
+
+    let kind = if object.kind.is_empty() {
+        &object.name
+    } else {
+        &object.kind
+    };
+    let objects = &mut config.objects;
+    match kind.to_lowercase().as_str() {
+        "block" => objects.blocks.push(Block {
+            x: center.x,
+            y: center.y,
+            w: rect.width(),
+            h: rect.height(),
+            speed: Some(property(properties, "speed").unwrap_or(0.0)),
+        }),
+        "wall" => {
+            let wall = match &object.polygon {
+                Some(points) => Wall::Polygon {
+                    points: points
+                        .iter()
+                        .map(|point| to_world(object.x + point.x, object.y + point.y).into())
+                        .collect(),
+                },
+                None => Wall::from_rect(rect),
+            };
+            wall.validate()?;
+            config.walls.push(wall);
+        }
+        hazard @ ("spikes" | "saw" | "kill_zone") => {
+            let kind: HazardKind = serde_json::from_value(hazard.into())?;
+            objects.hazards.push(Hazard {
+                kind,
+                x: center.x,
+                y: center.y,
+                w: rect.width(),
+                h: rect.height(),
+                speed: property(properties, "speed").unwrap_or(0.0),
+            });
+        }
+        "goal" => {
+            let goal = Goal {
+                x: center.x,
+                y: center.y - bottom_y,
+                w: (rect.width() > 0.0).then_some(rect.width()),
+                h: (rect.height() > 0.0).then_some(rect.height()),
+                requires_all_collectibles: property(properties, "requires_all_collectibles")
+                    .unwrap_or(false),
+            };
+            if objects.goal.is_none() {
+                objects.goal = Some(goal);
+            } else {
+                objects.goals.push(goal);
+            }
+        }
+        "start" => {
+            objects.player.x = center.x;
+            objects.player.y = bottom + player_size / 2.0 - bottom_y;
+        }
+        "checkpoint" => objects.checkpoints.push(Checkpoint {
+            x: center.x,
+            y: bottom + player_size,
+        }),
+        "coin" | "gem" => objects.collectibles.push(Collectible {
+            kind: if kind.eq_ignore_ascii_case("coin") {
+                CollectibleKind::Coin
+            } else {
+                CollectibleKind::Gem
+            },
+            x: center.x,
+            y: center.y,
+            block: None,
+        }),
+        other => bail!("Unknown object type {other:?}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    /// A 10 by 10 map of 10 pixel tiles, so 100 by 100 pixels centered on the
+    /// origin, with `layers` and optionally a `template`.
+    fn map(layers: Value, template: Option<&str>) -> String {
+        let properties: Vec<Value> = template
+            .map(|template| json!({ "name": "template", "type": "file", "value": template }))
+            .into_iter()
+            .collect();
+        json!({
+            "width": 10,
+            "height": 10,
+            "tilewidth": 10,
+            "tileheight": 10,
+            "layers": layers,
+            "properties": properties,
+        })
+        .to_string()
+    }
+
+    fn objects(objects: Value) -> String {
+        map(json!([{ "type": "objectgroup", "objects": objects }]), None)
+    }
+
+    fn tiles(width: usize, height: usize, data: &[u32]) -> String {
+        json!({
+            "width": width,
+            "height": height,
+            "tilewidth": 10,
+            "tileheight": 10,
+            "layers": [{ "type": "tilelayer", "data": data }],
+        })
+        .to_string()
+    }
+
+    fn load(data: &str) -> anyhow::Result<Config> {
+        load_map("assets/levels/test.tmj", data)
+    }
+
+    #[test]
+    fn objects_are_flipped_into_world_space() {
+        let data = objects(json!([
+            { "type": "block", "x": 0, "y": 0, "width": 20, "height": 10 },
+        ]));
+        let block = &load(&data).unwrap().objects.blocks[0];
+        assert_eq!(
+            (block.x, block.y, block.w, block.h),
+            (-40.0, 45.0, 20.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn tile_objects_are_placed_by_their_bottom_left_corner() {
+        let data = objects(json!([
+            { "type": "coin", "gid": 1, "x": 0, "y": 10, "width": 10, "height": 10 },
+            { "type": "coin", "x": 0, "y": 10, "width": 10, "height": 10 },
+        ]));
+        let config = load(&data).unwrap();
+        let [tile, rect] = &config.objects.collectibles[..] else {
+            panic!("expected two collectibles");
+        };
+        assert_eq!((tile.x, tile.y), (-45.0, 45.0));
+        assert_eq!((rect.x, rect.y), (-45.0, 35.0));
+    }
+
+    #[test]
+    fn polygon_walls_are_flipped_too() {
+        let data = objects(json!([{
+            "type": "wall",
+            "x": 50,
+            "y": 50,
+            "polygon": [{ "x": 0, "y": 0 }, { "x": 10, "y": 0 }, { "x": 0, "y": -10 }],
+        }]));
+        let config = load(&data).unwrap();
+        assert_eq!(
+            config.walls,
+            vec![Wall::Polygon {
+                points: vec![[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]]
+            }]
+        );
+    }
+
+    #[test]
+    fn object_kinds_come_from_type_class_or_name() {
+        let data = objects(json!([
+            { "type": "Spikes", "x": 0, "y": 90, "width": 20, "height": 10 },
+            {
+                "class": "saw",
+                "x": 40,
+                "y": 40,
+                "width": 10,
+                "height": 10,
+                "properties": [{ "name": "speed", "type": "float", "value": 50 }],
+            },
+            { "name": "gem", "x": 60, "y": 20 },
+            { "name": "flag", "type": "goal", "x": 80, "y": 0, "width": 10, "height": 20 },
+            { "name": "start", "x": 10, "y": 60, "width": 10, "height": 10 },
+        ]));
+        let config = load(&data).unwrap();
+        let objects = &config.objects;
+        let hazards: Vec<_> = objects
+            .hazards
+            .iter()
+            .map(|hazard| (hazard.kind, hazard.speed))
+            .collect();
+        assert_eq!(
+            hazards,
+            [(HazardKind::Spikes, 0.0), (HazardKind::Saw, 50.0)]
+        );
+        assert_eq!(objects.collectibles[0].kind, CollectibleKind::Gem);
+        let goal = objects.goal.as_ref().unwrap();
+        assert_eq!(goal.x, 35.0);
+        assert_eq!(goal.y, 40.0 - config.wall_params.bottom_y);
+        let player = &objects.player;
+        assert_eq!(player.x, -35.0);
+        assert_eq!(
+            player.y,
+            -20.0 + player.size / 2.0 - config.wall_params.bottom_y
+        );
+    }
+
+    #[test]
+    fn unknown_object_types_are_refused() {
+        let data = objects(json!([{ "type": "dragon", "x": 0, "y": 0 }]));
+        assert!(load(&data).is_err());
+    }
+
+    #[test]
+    fn tile_layers_merge_into_walls() {
+        #[rustfmt::skip]
+        let data = tiles(4, 3, &[
+            0, 1, 0, 0,
+            0, 0, 0, 0,
+            2, 2, 2, 2,
+        ]);
+        let config = load(&data).unwrap();
+        let mut walls: Vec<Rect> = config.walls.iter().map(Wall::bounds).collect();
+        walls.sort_by(|a, b| a.min.y.total_cmp(&b.min.y));
+        assert_eq!(
+            walls,
+            [
+                Rect::new(-20.0, -15.0, 20.0, -5.0),
+                Rect::new(-10.0, 5.0, 0.0, 15.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_solid_tile_layers_are_decoration() {
+        let data = map(
+            json!([{
+                "type": "tilelayer",
+                "data": vec![1; 100],
+                "properties": [{ "name": "solid", "type": "bool", "value": false }],
+            }]),
+            None,
+        );
+        assert!(load(&data).unwrap().walls.is_empty());
+    }
+
+    #[test]
+    fn tile_layers_must_cover_the_map() {
+        assert!(load(&tiles(4, 3, &[1; 11])).is_err());
+        assert!(load(&tiles(4, 3, &[1; 13])).is_err());
+        assert!(load(&tiles(0, 3, &[])).is_err());
+    }
+
+    #[test]
+    fn templates_provide_everything_but_the_layout() {
+        let template = load_config("assets/levels/level2.json").unwrap();
+        let data = map(json!([]), Some("assets/levels/level2.json"));
+        let config = load(&data).unwrap();
+        assert_eq!(config.physics.gravity, template.physics.gravity);
+        assert_eq!(config.objects.player.speed, template.objects.player.speed);
+        assert!(config.walls.is_empty());
+        assert!(config.objects.blocks.is_empty());
+        assert!(config.objects.hazards.is_empty());
+    }
+
+    #[test]
+    fn maps_can_be_templates() {
+        let data = map(json!([]), Some("assets/levels/level5.tmj"));
+        assert!(load(&data).is_ok());
+    }
+
+    #[test]
+    fn template_cycles_are_refused() {
+        let data = map(json!([]), Some("assets/levels/./test.tmj"));
+        let err = load(&data).unwrap_err();
+        assert!(format!("{err:#}").contains("one of its own templates"));
+        // The shipped map's own template is the default level, which is where
+        // this map claims to live.
+        let data = map(json!([]), Some("assets/levels/level5.tmj"));
+        let err = load_map(DEFAULT_TEMPLATE, &data).unwrap_err();
+        assert!(format!("{err:#}").contains("one of its own templates"));
+    }
+
+    #[test]
+    fn shipped_map_loads() {
+        let config = load_config("assets/levels/level5.tmj").unwrap();
+        let objects = &config.objects;
+        assert!(objects.goal.is_some());
+        assert_eq!(objects.collectibles.len(), 3);
+        assert_eq!(objects.checkpoints.len(), 1);
+        assert_eq!(objects.hazards.len(), 1);
+        assert_eq!(objects.blocks.len(), 1);
+        // The player starts standing on the floor.
+        let floor_top = config.wall_params.bottom_y + config.wall_params.thickness / 2.0;
+        assert_eq!(
+            objects.player.y + config.wall_params.bottom_y - objects.player.size / 2.0,
+            floor_top
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use crate::resources::json_reader::{
+    Block, Checkpoint, Collectible, CollectibleKind, Config, Goal, Hazard, HazardKind, Wall,
+};
+use anyhow::bail;
+use bevy::prelude::{Rect, Vec2};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// What a character in a [`Tilemap`] stands for.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Tile {
+    Empty,
+    /// Solid; neighbouring wall tiles merge into larger walls.
+    Wall,
+    /// Static block; a run of pads in a row is one block.
+    Pad,
+    /// Block moving at the default speed.
+    MovingPad,
+    /// Spikes along the bottom half of the tile.
+    Spikes,
+    Saw,
+    KillZone,
+    Goal,
+    /// Where the player starts, standing on the tile below.
+    Start,
+    Checkpoint,
+    Coin,
+    Gem,
+}
+
+/// The characters every tilemap understands, unless its legend overrides them.
+fn default_legend() -> HashMap<char, Tile> {
+    HashMap::from([
+        (' ', Tile::Empty),
+        ('.', Tile::Empty),
+        ('#', Tile::Wall),
+        ('=', Tile::Pad),
+        ('~', Tile::MovingPad),
+        ('^', Tile::Spikes),
+        ('@', Tile::Saw),
+        ('x', Tile::KillZone),
+        ('G', Tile::Goal),
+        ('P', Tile::Start),
+        ('C', Tile::Checkpoint),
+        ('o', Tile::Coin),
+        ('*', Tile::Gem),
+    ])
+}
+
+/// A level laid out as a grid of characters instead of positioned objects.
+/// It is expanded into the config's walls and objects when the level is
+/// loaded. Such levels can't be saved from the game, as that would replace
+/// the grid with the expanded walls and objects.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Tilemap {
+    pub tile_size: f32,
+    /// World position of the grid's bottom-left corner. Defaults to centering
+    /// the grid on the origin.
+    #[serde(default)]
+    pub origin: Option<[f32; 2]>,
+    /// Top row first. Shorter rows are padded with empty tiles.
+    pub rows: Vec<String>,
+    /// Adds characters to the default legend or changes their meaning.
+    #[serde(default)]
+    pub legend: HashMap<char, Tile>,
+}
+
+/// A rectangle of tiles: column and row of its top-left tile, and its size.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Area {
+    pub col: usize,
+    pub row: usize,
+    pub cols: usize,
+    pub rows: usize,
+}
+
+impl Area {
+    /// The area in world coordinates, for a grid of `height` rows of
+    /// `tile`-sized tiles with its bottom-left corner at `origin`.
+    pub fn rect(&self, origin: Vec2, tile: Vec2, height: usize) -> Rect {
+        let min =
+            origin + Vec2::new(self.col as f32, (height - self.row - self.rows) as f32) * tile;
+        Rect::from_corners(
+            min,
+            min + Vec2::new(self.cols as f32, self.rows as f32) * tile,
+        )
+    }
+}
+
+impl Tilemap {
+    /// Adds the grid's walls and objects to `config`.
+    pub fn apply(&self, config: &mut Config) -> anyhow::Result<()> {
+        let grid = self.grid()?;
+        let height = grid.len();
+        let width = grid.first().map_or(0, Vec::len);
+        let tile = self.tile_size;
+        let origin = self
+            .origin
+            .map(Vec2::from)
+            .unwrap_or(Vec2::new(width as f32, height as f32) * tile / -2.0);
+        let rect = |area: Area| area.rect(origin, Vec2::splat(tile), height);
+
+        let bottom_y = config.wall_params.bottom_y;
+        let player_size = config.objects.player.size;
+
+        config.walls.extend(
+            merge(&grid, &Tile::Wall, true)
+                .into_iter()
+                .map(|area| Wall::from_rect(rect(area))),
+        );
+
+        let objects = &mut config.objects;
+        for (kind, speed) in [(Tile::Pad, Some(0.0)), (Tile::MovingPad, None)] {
+            for area in merge(&grid, &kind, false) {
+                let rect = rect(area);
+                objects.blocks.push(Block {
+                    x: rect.center().x,
+                    y: rect.center().y,
+                    w: rect.width(),
+                    h: rect.height(),
+                    speed,
+                });
+            }
+        }
+        for (kind, hazard_kind, vertical) in [
+            (Tile::Spikes, HazardKind::Spikes, false),
+            (Tile::KillZone, HazardKind::KillZone, true),
+        ] {
+            for area in merge(&grid, &kind, vertical) {
+                let mut rect = rect(area);
+                if hazard_kind == HazardKind::Spikes {
+                    rect.max.y = rect.min.y + tile / 2.0;
+                }
+                objects.hazards.push(Hazard {
+                    kind: hazard_kind,
+                    x: rect.center().x,
+                    y: rect.center().y,
+                    w: rect.width(),
+                    h: rect.height(),
+                    speed: 0.0,
+                });
+            }
+        }
+
+        let mut start = None;
+        for (row, tiles) in grid.iter().enumerate() {
+            for (col, &kind) in tiles.iter().enumerate() {
+                let cell = rect(Area {
+                    col,
+                    row,
+                    cols: 1,
+                    rows: 1,
+                });
+                let center = cell.center();
+                match kind {
+                    Tile::Saw => objects.hazards.push(Hazard {
+                        kind: HazardKind::Saw,
+                        x: center.x,
+                        y: center.y,
+                        w: tile,
+                        h: tile,
+                        speed: 150.0,
+                    }),
+                    Tile::Goal => {
+                        let goal = Goal {
+                            x: center.x,
+                            y: center.y - bottom_y,
+                            w: None,
+                            h: None,
+                            requires_all_collectibles: false,
+                        };
+                        if objects.goal.is_none() {
+                            objects.goal = Some(goal);
+                        } else {
+                            objects.goals.push(goal);
+                        }
+                    }
+                    Tile::Start => {
+                        if start.replace((row, col)).is_some() {
+                            bail!("More than one player start");
+                        }
+                        objects.player.x = center.x;
+                        objects.player.y = cell.min.y + player_size / 2.0 - bottom_y;
+                    }
+                    Tile::Checkpoint => objects.checkpoints.push(Checkpoint {
+                        x: center.x,
+                        y: cell.min.y + player_size,
+                    }),
+                    Tile::Coin | Tile::Gem => objects.collectibles.push(Collectible {
+                        kind: if kind == Tile::Coin {
+                            CollectibleKind::Coin
+                        } else {
+                            CollectibleKind::Gem
+                        },
+                        x: center.x,
+                        y: center.y,
+                        block: None,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The rows translated through the legend, padded to the same length.
+    fn grid(&self) -> anyhow::Result<Vec<Vec<Tile>>> {
+        let mut legend = default_legend();
+        legend.extend(&self.legend);
+        let width = self
+            .rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(row, line)| {
+                let mut tiles = line
+                    .chars()
+                    .enumerate()
+                    .map(|(col, c)| match legend.get(&c) {
+                        Some(&tile) => Ok(tile),
+                        None => bail!("Unknown tile '{c}' in row {}, column {}", row + 1, col + 1),
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                tiles.resize(width, Tile::Empty);
+                Ok(tiles)
+            })
+            .collect()
+    }
+}
+
+/// Covers the `kind` tiles with as few rectangles as a greedy scan finds:
+/// runs along each row, grown downwards over identical runs if `vertical`.
+/// Tiles past the end of a short row count as something else.
+pub(crate) fn merge<T: PartialEq>(grid: &[Vec<T>], kind: &T, vertical: bool) -> Vec<Area> {
+    let mut used: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
+    let free = |used: &Vec<Vec<bool>>, row: usize, col: usize| {
+        grid[row].get(col) == Some(kind) && !used[row][col]
+    };
+    let mut areas = Vec::new();
+    for row in 0..grid.len() {
+        for col in 0..grid[row].len() {
+            if !free(&used, row, col) {
+                continue;
+            }
+            let cols = (col..grid[row].len())
+                .take_while(|&c| free(&used, row, c))
+                .count();
+            let rows = if vertical {
+                (row..grid.len())
+                    .take_while(|&r| (col..col + cols).all(|c| free(&used, r, c)))
+                    .count()
+            } else {
+                1
+            };
+            for used_row in &mut used[row..row + rows] {
+                used_row[col..col + cols].fill(true);
+            }
+            areas.push(Area {
+                col,
+                row,
+                cols,
+                rows,
+            });
+        }
+    }
+    areas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::json_reader::{load_config, save_config};
+
+    fn tilemap(rows: &[&str]) -> Tilemap {
+        Tilemap {
+            tile_size: 10.0,
+            origin: None,
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            legend: HashMap::new(),
+        }
+    }
+
+    /// The default level with nothing in it.
+    fn empty_config() -> Config {
+        let mut config = load_config("assets/config.json").unwrap();
+        config.walls.clear();
+        config.objects.blocks.clear();
+        config.objects.goal = None;
+        config.objects.goals.clear();
+        config.objects.hazards.clear();
+        config.objects.checkpoints.clear();
+        config.objects.collectibles.clear();
+        config
+    }
+
+    #[test]
+    fn l_shape_merges_into_two_rectangles() {
+        let grid = tilemap(&["#..", "#..", "###"]).grid().unwrap();
+        let areas: Vec<_> = merge(&grid, &Tile::Wall, true)
+            .into_iter()
+            .map(|area| (area.col, area.row, area.cols, area.rows))
+            .collect();
+        assert_eq!(areas, [(0, 0, 1, 3), (1, 2, 2, 1)]);
+    }
+
+    #[test]
+    fn rows_merge_without_vertical() {
+        let grid = tilemap(&["##", "##"]).grid().unwrap();
+        assert_eq!(merge(&grid, &Tile::Wall, false).len(), 2);
+        assert_eq!(merge(&grid, &Tile::Wall, true).len(), 1);
+    }
+
+    #[test]
+    fn ragged_rows_are_padded_with_empty_tiles() {
+        let grid = tilemap(&["#", "###", ""]).grid().unwrap();
+        assert_eq!(
+            grid,
+            [
+                vec![Tile::Wall, Tile::Empty, Tile::Empty],
+                vec![Tile::Wall; 3],
+                vec![Tile::Empty; 3],
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_stops_at_short_rows() {
+        let grid = vec![vec![true, true], vec![true]];
+        let areas: Vec<_> = merge(&grid, &true, true)
+            .into_iter()
+            .map(|area| (area.col, area.row, area.cols, area.rows))
+            .collect();
+        assert_eq!(areas, [(0, 0, 2, 1), (0, 1, 1, 1)]);
+    }
+
+    #[test]
+    fn grid_is_centered_on_the_origin() {
+        let mut config = empty_config();
+        tilemap(&["#.", ".."]).apply(&mut config).unwrap();
+        assert_eq!(
+            config.walls,
+            [Wall::Rect {
+                x: -5.0,
+                y: 5.0,
+                w: 10.0,
+                h: 10.0
+            }]
+        );
+    }
+
+    #[test]
+    fn player_stands_on_the_tile_below_the_start() {
+        let mut config = empty_config();
+        let mut map = tilemap(&[".P", "##"]);
+        map.origin = Some([100.0, 0.0]);
+        map.apply(&mut config).unwrap();
+        let player = &config.objects.player;
+        assert_eq!(player.x, 115.0);
+        assert_eq!(
+            player.y,
+            10.0 + player.size / 2.0 - config.wall_params.bottom_y
+        );
+        assert_eq!(
+            config.walls,
+            [Wall::Rect {
+                x: 110.0,
+                y: 5.0,
+                w: 20.0,
+                h: 10.0
+            }]
+        );
+    }
+
+    #[test]
+    fn second_player_start_is_rejected() {
+        let err = tilemap(&["P.P"]).apply(&mut empty_config()).unwrap_err();
+        assert!(err.to_string().contains("More than one player start"));
+    }
+
+    #[test]
+    fn unknown_character_is_rejected() {
+        let err = tilemap(&["#?"]).apply(&mut empty_config()).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown tile '?' in row 1, column 2");
+    }
+
+    #[test]
+    fn legend_adds_characters() {
+        let mut map = tilemap(&["#?"]);
+        map.legend.insert('?', Tile::Wall);
+        let grid = map.grid().unwrap();
+        assert_eq!(merge(&grid, &Tile::Wall, true).len(), 1);
+    }
+
+    #[test]
+    fn tilemap_levels_are_not_saved() {
+        let config = load_config("assets/levels/level4.json").unwrap();
+        assert!(config.tilemap.is_some());
+        assert!(save_config(&config, "target/tilemap_level.json").is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use super::BACKGROUND_Z;
+use crate::components;
+use crate::resources;
+use bevy::prelude::*;
+
+/// Spawns the level's parallax backgrounds. `texture_backgrounds_system`
+/// gives them their images and `parallax_system` places them.
+pub fn spawn_backgrounds(commands: &mut Commands, config: &Res<resources::json_reader::Config>) {
+    for (index, background) in config.backgrounds.iter().enumerate() {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(0.0, 0.0, BACKGROUND_Z + index as f32),
+                sprite: Sprite {
+                    color: Color::srgb_from_array(background.tint),
+                    ..default()
+                },
+                ..default()
+            },
+            components::ParallaxLayer {
+                background: background.clone(),
+                scrolled: Vec2::ZERO,
+            },
+            components::LevelEntity,
+        ));
+    }
+}
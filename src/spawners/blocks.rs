@@ -2,18 +2,32 @@ use crate::components;
 use crate::resources;
 use bevy::prelude::*;
 
-pub fn spawn_blocks(commands: &mut Commands, config: &Res<resources::json_reader::Config>) {
+pub fn spawn_blocks(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    let color = theme.pad_color(config);
     for (index, block) in config.objects.blocks.iter().enumerate() {
-        let vertical_speed = block.y + 300.0 + (index as f32 * 25.0);
-        let b_index = match index % 2 == 0 {
-            true => 1.0,
-            false => -1.0,
-        };
-        commands.spawn((
-            components::BlockBundle::new(block),
-            components::Block(index),
-            components::Direction(b_index as f32),
-            components::Velocity(Vec2::new(vertical_speed * b_index, 0.0)),
-        ));
+        spawn_block(commands, index, block, color);
     }
 }
+
+/// `index` is the block's index in the config's `blocks`, or for blocks not
+/// from the config, a number no other block has.
+pub fn spawn_block(
+    commands: &mut Commands,
+    index: usize,
+    block: &resources::json_reader::Block,
+    color: Color,
+) {
+    let speed = block.speed(index);
+    commands.spawn((
+        components::BlockBundle::new(block, color),
+        components::LevelEntity,
+        components::Block(index),
+        components::BlockSize(Vec2::new(block.w, block.h)),
+        components::Direction(speed.signum()),
+        components::Velocity(Vec2::new(speed, 0.0)),
+    ));
+}
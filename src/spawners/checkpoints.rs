@@ -0,0 +1,31 @@
+use super::LEVEL_Z;
+use crate::components;
+use crate::resources;
+use bevy::prelude::*;
+
+pub fn spawn_checkpoints(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    let size = Vec2::new(
+        config.objects.player.size / 2.0,
+        config.objects.player.size * 2.0,
+    );
+    for checkpoint in config.objects.checkpoints.iter() {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(checkpoint.x, checkpoint.y, LEVEL_Z),
+                sprite: Sprite {
+                    color: theme.checkpoint_color(false),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
+            components::Checkpoint { active: false },
+            components::Sensor(size),
+            components::LevelEntity,
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+use super::LEVEL_Z;
+use crate::components;
+use crate::resources;
+use crate::resources::json_reader::CollectibleKind;
+use bevy::prelude::*;
+
+pub fn spawn_collectibles(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    commands.insert_resource(resources::level_state::Score {
+        total: config.objects.collectibles.len(),
+        ..default()
+    });
+
+    for collectible in config.objects.collectibles.iter() {
+        let (color, size) = match collectible.kind {
+            CollectibleKind::Coin => (Color::srgb_from_array(theme.coin), Vec2::splat(14.0)),
+            CollectibleKind::Gem => (Color::srgb_from_array(theme.gem), Vec2::splat(20.0)),
+        };
+        // Blocks start at their configured position, so the offset can be applied up front.
+        let position = match collectible.block.and_then(|i| config.objects.blocks.get(i)) {
+            Some(block) => Vec2::new(block.x + collectible.x, block.y + collectible.y),
+            None => Vec2::new(collectible.x, collectible.y),
+        };
+        let mut entity = commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_translation(position.extend(LEVEL_Z)),
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                ..default()
+            },
+            components::Collectible {
+                points: collectible.kind.points(),
+            },
+            components::Sensor(size),
+            components::LevelEntity,
+        ));
+        if let Some(block) = collectible.block {
+            entity.insert(components::RidesBlock {
+                block,
+                offset: Vec2::new(collectible.x, collectible.y),
+            });
+        }
+    }
+}
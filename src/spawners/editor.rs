@@ -0,0 +1,36 @@
+use crate::components;
+use crate::resources::theme::Theme;
+use crate::states::GameState;
+use bevy::prelude::*;
+
+const HELP: &str = "F2 play-test   Click select   Drag move   Shift+drag resize   Del delete\n\
+1 block   2 spikes   3 saw   4 kill zone   5 checkpoint   6 goal   7 player start   8 wall\n\
+-/= speed   F flip direction   G grid snap   Arrows pan   Ctrl+S save";
+
+/// Help and status lines along the bottom of the screen while editing.
+pub fn spawn_editor_ui(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
+    let style = theme.text_style(&asset_server, 18.0);
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.0),
+                    left: Val::Px(10.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(6.0),
+                    ..default()
+                },
+                background_color: theme.panel().into(),
+                ..default()
+            },
+            StateScoped(GameState::Editor),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("", style.clone()),
+                components::EditorStatusText,
+            ));
+            parent.spawn(TextBundle::from_section(HELP, style));
+        });
+}
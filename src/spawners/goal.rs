@@ -1,22 +1,37 @@
+use super::LEVEL_Z;
 use crate::components;
 use crate::resources;
 use bevy::prelude::*;
 
-pub fn spawn_goal(commands: &mut Commands, config: &Res<resources::json_reader::Config>) {
-    let goal_y = config.objects.goal.y + config.wall_params.bottom_y;
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(config.objects.goal.x, goal_y, 0.0),
-                scale: Vec3::splat(config.objects.player.size),
+pub fn spawn_goal(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    commands.insert_resource(resources::level_state::LevelCompleted(false));
+
+    for goal in config.objects.all_goals() {
+        let goal_y = goal.y + config.wall_params.bottom_y;
+        let size = Vec2::new(
+            goal.w.unwrap_or(config.objects.player.size),
+            goal.h.unwrap_or(config.objects.player.size),
+        );
+        let locked = goal.requires_all_collectibles && !config.objects.collectibles.is_empty();
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(goal.x, goal_y, LEVEL_Z),
+                sprite: Sprite {
+                    color: theme.goal_color(config, locked),
+                    custom_size: Some(size),
+                    ..default()
+                },
                 ..default()
             },
-            sprite: Sprite {
-                color: Color::srgb(1.0, 0.5, 0.5),
-                ..default()
+            components::Goal {
+                requires_all_collectibles: goal.requires_all_collectibles,
             },
-            ..default()
-        },
-        components::Goal,
-    ));
+            components::Sensor(size),
+            components::LevelEntity,
+        ));
+    }
 }
@@ -0,0 +1,22 @@
+use crate::components;
+use crate::resources;
+use bevy::prelude::*;
+
+pub fn spawn_hazards(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    for hazard in config.objects.hazards.iter() {
+        let mut entity = commands.spawn((
+            components::HazardBundle::new(hazard, theme.hazard_color(hazard.kind)),
+            components::LevelEntity,
+        ));
+        if hazard.kind == resources::json_reader::HazardKind::Saw {
+            entity.insert((
+                components::Direction(hazard.speed.signum()),
+                components::Velocity(Vec2::new(hazard.speed, 0.0)),
+            ));
+        }
+    }
+}
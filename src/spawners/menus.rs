@@ -0,0 +1,281 @@
+use crate::components::{MenuButton, SettingsButton};
+use crate::resources;
+use crate::resources::key_bindings::Action;
+use crate::resources::theme::Theme;
+use crate::states::GameState;
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+/// Full-screen column that is despawned when `state` is left.
+fn spawn_screen<'a>(
+    commands: &'a mut Commands,
+    state: GameState,
+    background: Color,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            background_color: background.into(),
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        StateScoped(state),
+    ))
+}
+
+/// `action` is the component that tells the menu systems what the button does.
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    label: &str,
+    action: impl Bundle,
+    style: &TextStyle,
+    theme: &Theme,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(260.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: theme.button_color(Interaction::None).into(),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(label, style.clone()));
+        });
+}
+
+pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
+    let style = theme.text_style(&asset_server, 30.0);
+    spawn_screen(&mut commands, GameState::MainMenu, theme.menu()).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Jumpar",
+            TextStyle {
+                color: theme.highlight(),
+                ..theme.text_style(&asset_server, 70.0)
+            },
+        ));
+        spawn_button(parent, "Play", MenuButton::Play, &style, &theme);
+        spawn_button(
+            parent,
+            "Level select",
+            MenuButton::LevelSelect,
+            &style,
+            &theme,
+        );
+        spawn_button(parent, "Endless", MenuButton::Endless, &style, &theme);
+        spawn_button(parent, "Settings", MenuButton::Settings, &style, &theme);
+        spawn_button(parent, "Quit", MenuButton::Quit, &style, &theme);
+    });
+}
+
+/// Grid of levels; locked levels are shown but can't be picked.
+pub fn spawn_level_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    level_list: Res<resources::level_list::LevelList>,
+    save_data: Res<resources::save_data::SaveData>,
+) {
+    let style = theme.text_style(&asset_server, 24.0);
+    let detail_style = theme.text_style(&asset_server, 18.0);
+    spawn_screen(&mut commands, GameState::LevelSelect, theme.menu()).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Select level",
+            theme.text_style(&asset_server, 50.0),
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::px(3, 220.0),
+                    row_gap: Val::Px(10.0),
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|grid| {
+                for (index, level) in level_list.levels.iter().enumerate() {
+                    let unlocked = index == 0 || save_data.is_unlocked(&level.path);
+                    let best_time = save_data
+                        .records
+                        .get(&level.path)
+                        .and_then(|record| record.best_time);
+                    let detail = match (unlocked, best_time) {
+                        (false, _) => "Locked".to_string(),
+                        (true, Some(time)) => format!("Best: {time:.2}s"),
+                        (true, None) => "Not completed".to_string(),
+                    };
+                    let cell_style = Style {
+                        flex_direction: FlexDirection::Column,
+                        padding: UiRect::all(Val::Px(10.0)),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    };
+                    let mut cell = if unlocked {
+                        grid.spawn((
+                            ButtonBundle {
+                                style: cell_style,
+                                background_color: theme.button_color(Interaction::None).into(),
+                                ..default()
+                            },
+                            MenuButton::PlayLevel(index),
+                        ))
+                    } else {
+                        grid.spawn(NodeBundle {
+                            style: cell_style,
+                            background_color: theme.button_locked().into(),
+                            ..default()
+                        })
+                    };
+                    cell.with_children(|cell| {
+                        cell.spawn(TextBundle::from_section(
+                            format!("{}. {}", index + 1, level.name),
+                            style.clone(),
+                        ));
+                        cell.spawn(TextBundle::from_section(detail, detail_style.clone()));
+                    });
+                }
+            });
+        spawn_button(parent, "Back", MenuButton::Back, &style, &theme);
+    });
+}
+
+/// Display and audio options on the left, key bindings on the right.
+pub fn spawn_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    save_data: Res<resources::save_data::SaveData>,
+) {
+    let style = theme.text_style(&asset_server, 24.0);
+    let column = NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        ..default()
+    };
+    let general = [
+        SettingsButton::WindowMode,
+        SettingsButton::Resolution,
+        SettingsButton::Vsync,
+        SettingsButton::MasterVolume,
+        SettingsButton::MusicVolume,
+        SettingsButton::SfxVolume,
+        SettingsButton::ShowFps,
+    ];
+
+    spawn_screen(&mut commands, GameState::Settings, theme.menu()).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Settings",
+            theme.text_style(&asset_server, 50.0),
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|columns| {
+                columns.spawn(column.clone()).with_children(|column| {
+                    for button in general {
+                        let label = button.label(&save_data.settings, None);
+                        spawn_button(column, &label, button, &style, &theme);
+                    }
+                });
+                columns.spawn(column.clone()).with_children(|column| {
+                    for action in Action::ALL {
+                        let button = SettingsButton::Rebind(action);
+                        let label = button.label(&save_data.settings, None);
+                        spawn_button(column, &label, button, &style, &theme);
+                    }
+                });
+            });
+        spawn_button(parent, "Back", MenuButton::Back, &style, &theme);
+    });
+}
+
+/// Translucent overlay on top of the frozen level.
+pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
+    let style = theme.text_style(&asset_server, 30.0);
+    spawn_screen(&mut commands, GameState::Paused, theme.panel()).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Paused",
+            theme.text_style(&asset_server, 50.0),
+        ));
+        spawn_button(parent, "Resume", MenuButton::Resume, &style, &theme);
+        spawn_button(parent, "Restart", MenuButton::Restart, &style, &theme);
+        spawn_button(
+            parent,
+            "Quit to menu",
+            MenuButton::QuitToMenu,
+            &style,
+            &theme,
+        );
+    });
+}
+
+/// The finished run's height and the high-score table, with this run highlighted.
+pub fn spawn_run_over_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    run: Res<resources::endless::EndlessRun>,
+    save_data: Res<resources::save_data::SaveData>,
+) {
+    let style = theme.text_style(&asset_server, 30.0);
+    let score_style = theme.text_style(&asset_server, 22.0);
+    spawn_screen(&mut commands, GameState::RunOver, theme.panel()).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "Run over",
+            theme.text_style(&asset_server, 50.0),
+        ));
+        parent.spawn(TextBundle::from_section(
+            format!("Height: {:.0}", run.height),
+            style.clone(),
+        ));
+        parent.spawn(TextBundle::from_section(
+            "Best heights",
+            score_style.clone(),
+        ));
+        for (rank, height) in save_data.endless_scores.iter().enumerate() {
+            let color = if run.rank == Some(rank) {
+                theme.highlight()
+            } else {
+                Color::srgb_from_array(theme.hud.color)
+            };
+            parent.spawn(TextBundle::from_section(
+                format!("{}. {height}", rank + 1),
+                TextStyle {
+                    color,
+                    ..score_style.clone()
+                },
+            ));
+        }
+        spawn_button(parent, "Retry", MenuButton::Endless, &style, &theme);
+        spawn_button(
+            parent,
+            "Quit to menu",
+            MenuButton::QuitToMenu,
+            &style,
+            &theme,
+        );
+    });
+}
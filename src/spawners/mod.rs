@@ -1,5 +1,41 @@
+use crate::resources;
+use bevy::prelude::*;
+
+pub mod backgrounds;
 pub mod blocks;
+pub mod checkpoints;
+pub mod collectibles;
+pub mod editor;
 pub mod goal;
+pub mod hazards;
+pub mod menus;
 pub mod player;
+pub mod results;
 pub mod ui;
 pub mod walls;
+
+/// Parallax backgrounds are stacked from here up, one z unit apart, furthest
+/// first. The 2D camera sees nothing below z = 0.
+pub const BACKGROUND_Z: f32 = 0.0;
+/// Walls, blocks, hazards, checkpoints, collectibles and the goal, in front of
+/// up to 100 background layers.
+pub const LEVEL_Z: f32 = 100.0;
+pub const PLAYER_Z: f32 = LEVEL_Z + 1.0;
+pub const PARTICLE_Z: f32 = PLAYER_Z + 1.0;
+
+/// Spawns every entity described by the level JSON, colored by `theme`.
+pub fn spawn_level(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    commands.insert_resource(resources::level_state::LevelTimer::default());
+    backgrounds::spawn_backgrounds(commands, config);
+    player::spawn_player(commands, config, theme);
+    goal::spawn_goal(commands, config, theme);
+    walls::spawn_walls(commands, config, theme);
+    blocks::spawn_blocks(commands, config, theme);
+    hazards::spawn_hazards(commands, config, theme);
+    checkpoints::spawn_checkpoints(commands, config, theme);
+    collectibles::spawn_collectibles(commands, config, theme);
+}
@@ -1,27 +1,38 @@
+use super::PLAYER_Z;
 use crate::components;
 use crate::resources;
 use bevy::prelude::*;
 
-pub fn spawn_player(commands: &mut Commands, config: &Res<resources::json_reader::Config>) {
+pub fn spawn_player(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
     let player_y = config.objects.player.y + config.wall_params.bottom_y;
+    let player_x = config.objects.player.x;
+    commands.insert_resource(resources::level_state::RespawnPoint(Vec2::new(
+        player_x, player_y,
+    )));
 
     commands.spawn((
         SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(0.0, player_y, 0.0),
-                scale: Vec3::splat(config.objects.player.size),
-                ..default()
-            },
+            transform: Transform::from_xyz(player_x, player_y, PLAYER_Z),
             sprite: Sprite {
-                color: Color::srgb(0.5, 0.5, 1.0),
+                color: theme.player_color(config),
+                custom_size: Some(Vec2::splat(config.objects.player.size)),
                 ..default()
             },
             ..default()
         },
         components::Player,
+        components::LevelEntity,
         components::Collider,
         components::Velocity(Vec2::ZERO),
         components::Mass(config.objects.player.mass),
-        components::PlayerState { grounded: false },
+        components::PlayerState {
+            grounded: false,
+            wall_side: 0.0,
+        },
+        components::player::PlayerAnimation::default(),
     ));
 }
@@ -0,0 +1,92 @@
+use crate::components;
+use crate::resources;
+use crate::resources::json_reader::Medal;
+use crate::resources::theme::Theme;
+use bevy::prelude::*;
+
+pub fn spawn_results_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+    config: Res<resources::json_reader::Config>,
+    timer: Res<resources::level_state::LevelTimer>,
+    death_count: Res<resources::level_state::DeathCount>,
+    score: Res<resources::level_state::Score>,
+) {
+    let style = theme.text_style(&asset_server, 30.0);
+    let seconds = timer.elapsed.as_secs_f32();
+
+    let mut lines = vec![
+        (format!("Time: {seconds:.2}s"), style.clone()),
+        (format!("Deaths: {}", death_count.0), style.clone()),
+        (
+            format!(
+                "Collected: {}/{} ({} points)",
+                score.collected, score.total, score.points
+            ),
+            style.clone(),
+        ),
+    ];
+    if let Some(par_times) = config.par_times {
+        let medal = match par_times.medal(seconds) {
+            Some(Medal::Gold) => "Gold",
+            Some(Medal::Silver) => "Silver",
+            Some(Medal::Bronze) => "Bronze",
+            None => "None",
+        };
+        lines.push((
+            format!("Medal: {medal}"),
+            TextStyle {
+                color: theme.highlight(),
+                ..style.clone()
+            },
+        ));
+        lines.push((
+            format!(
+                "Gold {:.1}s / Silver {:.1}s / Bronze {:.1}s",
+                par_times.gold, par_times.silver, par_times.bronze
+            ),
+            TextStyle {
+                font_size: 20.0,
+                ..style.clone()
+            },
+        ));
+    }
+    lines.push((
+        "Press Enter to continue".to_string(),
+        TextStyle {
+            font_size: 20.0,
+            ..style.clone()
+        },
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: theme.panel().into(),
+                ..default()
+            },
+            components::ResultsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Level complete!",
+                TextStyle {
+                    font_size: 50.0,
+                    ..style.clone()
+                },
+            ));
+            for (line, style) in lines {
+                parent.spawn(TextBundle::from_section(line, style));
+            }
+        });
+}
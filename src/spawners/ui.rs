@@ -1,35 +1,114 @@
 use crate::components;
-use bevy::{color::palettes::css::GOLD, prelude::*};
+use crate::resources::theme::Theme;
+use bevy::prelude::*;
 
-pub fn spawn_ui(commands: &mut Commands, asset_server: &Res<AssetServer>) {
+pub fn spawn_ui(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
     commands.spawn((
         // Create a TextBundle that has a Text with a list of sections.
         TextBundle::from_sections([
-            TextSection::new(
-                "FPS: ",
-                TextStyle {
-                    // This font is loaded and will be used instead of the default font.
-                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                    font_size: 30.0,
-                    ..default()
-                },
-            ),
+            // This font is loaded and will be used instead of the default font.
+            TextSection::new("FPS: ", theme.text_style(&asset_server, 30.0)),
             TextSection::from_style(if cfg!(feature = "default_font") {
                 TextStyle {
                     font_size: 60.0,
-                    color: GOLD.into(),
+                    color: theme.highlight(),
                     // If no font is specified, the default font (a minimal subset of FiraMono) will be used.
                     ..default()
                 }
             } else {
                 // "default_font" feature is unavailable, load a font to use instead.
                 TextStyle {
-                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                    font_size: 30.0,
-                    color: GOLD.into(),
+                    color: theme.highlight(),
+                    ..theme.text_style(&asset_server, 30.0)
                 }
             }),
         ]),
         components::FpsText,
     ));
 }
+
+/// Level counters shown in the top-right corner.
+pub fn spawn_counters(mut commands: Commands, asset_server: Res<AssetServer>, theme: Res<Theme>) {
+    let style = theme.text_style(&asset_server, 30.0);
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                right: Val::Px(10.0),
+                column_gap: Val::Px(20.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection::new("Deaths: ", style.clone()),
+                    TextSection::new(
+                        "0",
+                        TextStyle {
+                            color: theme.highlight(),
+                            ..style.clone()
+                        },
+                    ),
+                ]),
+                components::DeathText,
+            ));
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection::new("Score: ", style.clone()),
+                    TextSection::new(
+                        "0",
+                        TextStyle {
+                            color: theme.highlight(),
+                            ..style.clone()
+                        },
+                    ),
+                ]),
+                components::ScoreText,
+            ));
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection::new("Time: ", style.clone()),
+                    TextSection::new(
+                        "0.0",
+                        TextStyle {
+                            color: theme.highlight(),
+                            ..style.clone()
+                        },
+                    ),
+                ]),
+                components::TimeText,
+            ));
+        });
+}
+
+/// Climb height of the current endless run, below the level counters.
+pub fn spawn_height_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    theme: Res<Theme>,
+) {
+    let style = theme.text_style(&asset_server, 30.0);
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new("Height: ", style.clone()),
+            TextSection::new(
+                "0",
+                TextStyle {
+                    color: theme.highlight(),
+                    ..style
+                },
+            ),
+        ])
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(36.0),
+            right: Val::Px(10.0),
+            ..default()
+        }),
+        components::HeightText,
+        components::EndlessEntity,
+    ));
+}
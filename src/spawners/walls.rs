@@ -2,17 +2,21 @@ use crate::components;
 use crate::resources;
 use bevy::prelude::*;
 
-pub fn spawn_walls(commands: &mut Commands, config: &Res<resources::json_reader::Config>) {
-    commands.spawn(components::WallBundle::new(
-        components::WallLocation::Bottom,
-        &config,
-    ));
-    commands.spawn(components::WallBundle::new(
-        components::WallLocation::Left,
-        &config,
-    ));
-    commands.spawn(components::WallBundle::new(
-        components::WallLocation::Right,
-        &config,
-    ));
+pub fn spawn_walls(
+    commands: &mut Commands,
+    config: &Res<resources::json_reader::Config>,
+    theme: &Res<resources::theme::Theme>,
+) {
+    if config.wall_params.lava_floor {
+        commands.spawn((
+            components::HazardBundle::lava_floor(config, theme),
+            components::LevelEntity,
+        ));
+    }
+    for (index, wall) in config.walls().iter().enumerate() {
+        commands.spawn((
+            components::WallBundle::new(index, wall, theme.wall_color(config)),
+            components::LevelEntity,
+        ));
+    }
 }
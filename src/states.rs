@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    MainMenu,
+    LevelSelect,
+    Settings,
+    #[default]
+    Playing,
+    /// Gameplay is frozen behind the pause overlay.
+    Paused,
+    /// The level is complete and the results screen is shown.
+    Results,
+    /// Gameplay is frozen while the level is edited with the mouse.
+    Editor,
+    /// An endless run has ended and the high-score table is shown.
+    RunOver,
+}
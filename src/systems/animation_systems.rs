@@ -0,0 +1,179 @@
+use crate::components;
+use crate::components::player::{AnimationState, PlayerAnimation};
+use crate::events;
+use crate::resources;
+use crate::resources::animation::PlayerAnimations;
+use bevy::prelude::*;
+
+/// Reads the animation file the level's sprites name, unless it is the one
+/// already loaded. A file that fails to load leaves the player unanimated
+/// rather than being retried every frame.
+pub fn load_player_animations_system(
+    mut commands: Commands,
+    config: Res<resources::json_reader::Config>,
+    animations: Option<Res<PlayerAnimations>>,
+) {
+    let path = config.sprites.player_animations.as_deref();
+    if animations
+        .as_ref()
+        .map(|animations| animations.path.as_str())
+        == path
+    {
+        return;
+    }
+    let Some(path) = path else {
+        commands.remove_resource::<PlayerAnimations>();
+        return;
+    };
+    let animations = PlayerAnimations::load(path).unwrap_or_else(|err| {
+        error!("Unable to load player animations {path}: {err:#}");
+        PlayerAnimations {
+            path: path.to_string(),
+            ..default()
+        }
+    });
+    commands.insert_resource(animations);
+}
+
+/// Picks the player's animation from their state and velocity, advances it
+/// and flips the sprite to face the way they are going.
+#[allow(clippy::type_complexity)]
+pub fn player_animation_system(
+    time: Res<Time>,
+    animations: Option<Res<PlayerAnimations>>,
+    mut landed_events: EventReader<events::PlayerLanded>,
+    mut query: Query<
+        (
+            &components::Velocity,
+            &components::PlayerState,
+            &mut PlayerAnimation,
+            &mut Sprite,
+            Option<&mut TextureAtlas>,
+        ),
+        With<components::Player>,
+    >,
+) {
+    let landed = landed_events.read().count() > 0;
+    let Ok((velocity, player_state, mut animation, mut sprite, atlas)) = query.get_single_mut()
+    else {
+        return;
+    };
+
+    let landing = animation.state == AnimationState::Land
+        && !animations
+            .as_ref()
+            .and_then(|animations| animations.get(AnimationState::Land))
+            .is_none_or(|land| land.finished(animation.elapsed));
+    let state = if !player_state.grounded {
+        if player_state.wall_side != 0.0 && velocity.y < 0.0 {
+            AnimationState::WallSlide
+        } else if velocity.y > 0.0 {
+            AnimationState::Jump
+        } else {
+            AnimationState::Fall
+        }
+    } else if velocity.x != 0.0 {
+        AnimationState::Run
+    } else if landed || landing {
+        AnimationState::Land
+    } else {
+        AnimationState::Idle
+    };
+    if state == animation.state && !landed {
+        animation.elapsed += time.delta_seconds();
+    } else {
+        animation.state = state;
+        animation.elapsed = 0.0;
+    }
+
+    // Wall-slide frames are drawn with the wall on the right, like facing it.
+    if state == AnimationState::WallSlide {
+        animation.facing_left = player_state.wall_side < 0.0;
+    } else if velocity.x != 0.0 {
+        animation.facing_left = velocity.x < 0.0;
+    }
+    sprite.flip_x = animation.facing_left;
+
+    let (Some(animations), Some(mut atlas)) = (animations, atlas) else {
+        return;
+    };
+    if let Some(current) = animations.get(state) {
+        if let Some(&index) = current.frames.get(current.frame(animation.elapsed)) {
+            atlas.index = index;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::json_reader::{load_config, Wall};
+    use crate::systems::physics::{
+        apply_gravity, apply_velocity, detect_collision_system, handle_collision_system,
+    };
+    use std::time::Duration;
+
+    /// One fixed tick of the physics the player goes through in a level,
+    /// followed by the animation.
+    fn tick(app: &mut App) {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f64(1.0 / 64.0));
+        app.update();
+    }
+
+    #[test]
+    fn a_resting_player_stays_grounded_and_idle() {
+        let config = load_config("assets/config.json").unwrap();
+        let floor = config.wall_params.floor();
+        let player_y = floor.max.y + config.objects.player.size / 2.0;
+        let mut app = App::new();
+        app.add_event::<events::Collision>()
+            .add_event::<events::PlayerLanded>()
+            .init_resource::<Time>()
+            .add_systems(
+                Update,
+                (
+                    apply_gravity,
+                    apply_velocity,
+                    detect_collision_system,
+                    handle_collision_system,
+                    player_animation_system,
+                )
+                    .chain(),
+            );
+        app.world_mut().spawn(components::WallBundle::new(
+            0,
+            &Wall::from_rect(floor),
+            Color::WHITE,
+        ));
+        let player = app
+            .world_mut()
+            .spawn((
+                components::Player,
+                components::Velocity(Vec2::ZERO),
+                components::Mass(config.objects.player.mass),
+                components::PlayerState {
+                    grounded: false,
+                    wall_side: 0.0,
+                },
+                PlayerAnimation::default(),
+                Sprite::default(),
+                Transform::from_xyz(0.0, player_y, 0.0),
+            ))
+            .id();
+        app.insert_resource(config);
+
+        let mut last_elapsed = -1.0;
+        for _ in 0..10 {
+            tick(&mut app);
+            let entity = app.world().entity(player);
+            assert!(entity.get::<components::PlayerState>().unwrap().grounded);
+            assert_eq!(entity.get::<Transform>().unwrap().translation.y, player_y);
+            let animation = entity.get::<PlayerAnimation>().unwrap();
+            assert_eq!(animation.state, AnimationState::Idle);
+            assert!(animation.elapsed > last_elapsed);
+            last_elapsed = animation.elapsed;
+        }
+    }
+}
@@ -8,34 +8,27 @@ pub fn move_block_system(
         &mut Transform,
         &mut components::Direction,
         &mut components::Velocity,
-        &components::Block,
+        &components::BlockSize,
     )>,
     config: Res<resources::json_reader::Config>,
 ) {
-    for ((_, block_transform, mut direction, mut block_velocity, _), block_config) in
-        block_query.iter_mut().zip(config.objects.blocks.iter())
-    {
+    for (_, block_transform, mut direction, mut block_velocity, size) in &mut block_query {
+        let half_width = size.0.x / 2.0;
         match direction.0 > 0.0 {
             true => {
-                let bound = config.wall_params.right_x
-                    - config.wall_params.thickness / 2.0
-                    - block_config.w / 2.0;
+                let bound =
+                    config.wall_params.right_x - config.wall_params.thickness / 2.0 - half_width;
                 if block_transform.translation.x >= bound {
                     direction.0 = -1.0;
                     block_velocity.x = -block_velocity.x;
-                } else {
-                    block_velocity.x = block_velocity.x;
                 }
             }
             false => {
-                let bound = config.wall_params.left_x
-                    + config.wall_params.thickness / 2.0
-                    + block_config.w / 2.0;
+                let bound =
+                    config.wall_params.left_x + config.wall_params.thickness / 2.0 + half_width;
                 if block_transform.translation.x <= bound {
                     direction.0 = 1.0;
                     block_velocity.x = -block_velocity.x;
-                } else {
-                    block_velocity.x = block_velocity.x;
                 }
             }
         };
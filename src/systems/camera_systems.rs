@@ -0,0 +1,117 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use bevy::prelude::*;
+
+/// Clamps a camera center so the view stays inside `bounds`, centering it on
+/// any axis where the level is smaller than the view.
+fn clamp_to_bounds(center: Vec2, half_view: Vec2, bounds: Rect) -> Vec2 {
+    let clamp_axis = |value: f32, half: f32, min: f32, max: f32| {
+        if max - min <= 2.0 * half {
+            (min + max) / 2.0
+        } else {
+            value.clamp(min + half, max - half)
+        }
+    };
+    Vec2::new(
+        clamp_axis(center.x, half_view.x, bounds.min.x, bounds.max.x),
+        clamp_axis(center.y, half_view.y, bounds.min.y, bounds.max.y),
+    )
+}
+
+/// Eases the camera after the player, leading them in the direction they run.
+/// Snaps straight to the player when the level is respawned.
+pub fn camera_follow_system(
+    player_query: Query<(&Transform, &components::Velocity, Ref<components::Player>)>,
+    mut camera_query: Query<(&mut components::FollowCamera, &OrthographicProjection)>,
+    config: Res<resources::json_reader::Config>,
+    time: Res<Time>,
+) {
+    let (Ok((player_transform, velocity, player)), Ok((mut camera, projection))) =
+        (player_query.get_single(), camera_query.get_single_mut())
+    else {
+        return;
+    };
+    let params = &config.camera;
+    let player_position = player_transform.translation.truncate();
+    let bounds = config.bounds();
+
+    if player.is_added() {
+        camera.focus = player_position;
+        camera.look_ahead = 0.0;
+        camera.position = clamp_to_bounds(player_position, projection.area.max, bounds);
+        return;
+    }
+
+    let half_zone = Vec2::from(params.dead_zone) / 2.0;
+    camera.focus = camera
+        .focus
+        .clamp(player_position - half_zone, player_position + half_zone);
+
+    // Exponential easing, so smoothing doesn't depend on the frame rate.
+    let ease = if params.smoothing > 0.0 {
+        1.0 - (-params.smoothing * time.delta_seconds()).exp()
+    } else {
+        1.0
+    };
+    if velocity.x != 0.0 {
+        let lead = params.look_ahead * velocity.x.signum();
+        camera.look_ahead += (lead - camera.look_ahead) * ease;
+    }
+
+    let target = clamp_to_bounds(
+        camera.focus + Vec2::new(camera.look_ahead, 0.0),
+        projection.area.max,
+        bounds,
+    );
+    camera.position = camera.position.lerp(target, ease);
+}
+
+/// Hard landings and deaths shake the screen.
+pub fn camera_shake_trigger_system(
+    mut landed_events: EventReader<events::PlayerLanded>,
+    mut death_events: EventReader<events::PlayerDied>,
+    mut camera_query: Query<&mut components::FollowCamera>,
+    config: Res<resources::json_reader::Config>,
+) {
+    let hard_landing = config.camera.hard_landing_speed;
+    let mut trauma: f32 = 0.0;
+    for landed in landed_events.read() {
+        if landed.speed > hard_landing {
+            // Half strength at the threshold, full at twice the speed.
+            trauma = trauma.max(0.5 * (landed.speed / hard_landing).min(2.0));
+        }
+    }
+    if death_events.read().count() > 0 {
+        trauma = 1.0;
+    }
+    if trauma == 0.0 {
+        return;
+    }
+    for mut camera in &mut camera_query {
+        camera.trauma = camera.trauma.max(trauma).min(1.0);
+    }
+}
+
+/// Moves the camera to its followed position plus any shake, and lets the
+/// shake settle.
+pub fn camera_shake_system(
+    mut camera_query: Query<(&mut Transform, &mut components::FollowCamera)>,
+    config: Res<resources::json_reader::Config>,
+    time: Res<Time>,
+) {
+    let params = &config.camera;
+    for (mut transform, mut camera) in &mut camera_query {
+        // Squaring makes weak shakes subtle and strong ones violent.
+        let strength = params.shake_amplitude * camera.trauma * camera.trauma;
+        let offset = Vec2::new(fastrand::f32() * 2.0 - 1.0, fastrand::f32() * 2.0 - 1.0) * strength;
+        transform.translation = (camera.position + offset).extend(transform.translation.z);
+
+        let settle = if params.shake_duration > 0.0 {
+            time.delta_seconds() / params.shake_duration
+        } else {
+            1.0
+        };
+        camera.trauma = (camera.trauma - settle).max(0.0);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use bevy::prelude::*;
+
+/// Makes the touched checkpoint the respawn point, deactivating the previous one.
+pub fn checkpoint_system(
+    mut contact_events: EventReader<events::SensorContact>,
+    mut checkpoint_query: Query<(Entity, &Transform, &mut components::Checkpoint, &mut Sprite)>,
+    mut respawn_point: ResMut<resources::level_state::RespawnPoint>,
+    theme: Res<resources::theme::Theme>,
+) {
+    let Some(touched) = contact_events
+        .read()
+        .map(|contact| contact.entity)
+        .find(|entity| {
+            checkpoint_query
+                .get(*entity)
+                .is_ok_and(|(_, _, checkpoint, _)| !checkpoint.active)
+        })
+    else {
+        return;
+    };
+
+    for (entity, transform, mut checkpoint, mut sprite) in &mut checkpoint_query {
+        checkpoint.active = entity == touched;
+        sprite.color = theme.checkpoint_color(checkpoint.active);
+        if checkpoint.active {
+            respawn_point.0 = transform.translation.truncate();
+        }
+    }
+}
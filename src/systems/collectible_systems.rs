@@ -0,0 +1,35 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use bevy::prelude::*;
+
+pub fn ride_block_system(
+    mut rider_query: Query<(&mut Transform, &components::RidesBlock), Without<components::Block>>,
+    block_query: Query<(&Transform, &components::Block)>,
+) {
+    for (mut transform, rides) in &mut rider_query {
+        if let Some((block_transform, _)) = block_query.iter().find(|(_, b)| b.0 == rides.block) {
+            transform.translation.x = block_transform.translation.x + rides.offset.x;
+            transform.translation.y = block_transform.translation.y + rides.offset.y;
+        }
+    }
+}
+
+pub fn pickup_system(
+    mut commands: Commands,
+    mut contact_events: EventReader<events::SensorContact>,
+    collectible_query: Query<&components::Collectible>,
+    mut score: ResMut<resources::level_state::Score>,
+    mut pickup_events: EventWriter<events::CollectiblePickedUp>,
+) {
+    for contact in contact_events.read() {
+        if let Ok(collectible) = collectible_query.get(contact.entity) {
+            score.points += collectible.points;
+            score.collected += 1;
+            pickup_events.send(events::CollectiblePickedUp {
+                points: collectible.points,
+            });
+            commands.entity(contact.entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::debug_overlay::{DebugOverlay, LastContacts};
+use bevy::{color::palettes::css, prelude::*};
+
+/// Velocities are drawn scaled down so fast movement stays on screen.
+const VELOCITY_SCALE: f32 = 0.25;
+
+pub fn toggle_debug_overlay_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F3) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+pub fn record_contacts_system(
+    mut collision_events: EventReader<events::Collision>,
+    mut last_contacts: ResMut<LastContacts>,
+) {
+    for collision in collision_events.read() {
+        last_contacts.0.insert(collision.entity, collision.side);
+    }
+}
+
+pub fn clear_contacts_system(
+    mut restart_events: EventReader<events::RestartLevel>,
+    mut last_contacts: ResMut<LastContacts>,
+) {
+    if !restart_events.is_empty() {
+        restart_events.clear();
+        last_contacts.0.clear();
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn draw_debug_overlay_system(
+    mut gizmos: Gizmos,
+    player_query: Query<
+        (&Transform, &components::Velocity, &components::PlayerState),
+        With<components::Player>,
+    >,
+    block_query: Query<
+        (Entity, &Transform, &Sprite),
+        (With<components::Block>, With<components::Collider>),
+    >,
+    wall_query: Query<(&Transform, &components::Wall)>,
+    sensor_query: Query<(&Transform, &components::Sensor)>,
+    moving_query: Query<
+        (&Transform, &components::Velocity),
+        (Without<components::Player>, Without<components::Sensor>),
+    >,
+    last_contacts: Res<LastContacts>,
+    config: Res<resources::json_reader::Config>,
+) {
+    for (_, transform, sprite) in &block_query {
+        gizmos.rect_2d(
+            transform.translation.truncate(),
+            0.0,
+            sprite.custom_size.unwrap_or_default(),
+            css::LIME,
+        );
+    }
+    for (transform, wall) in &wall_query {
+        let center = transform.translation.truncate();
+        let points = wall.points.iter().chain(wall.points.first());
+        gizmos.linestrip_2d(points.map(|point| center + *point), css::LIME);
+    }
+    for (transform, sensor) in &sensor_query {
+        gizmos.rect_2d(transform.translation.truncate(), 0.0, sensor.0, css::YELLOW);
+    }
+    for (transform, velocity) in &moving_query {
+        let start = transform.translation.truncate();
+        gizmos.arrow_2d(start, start + velocity.0 * VELOCITY_SCALE, css::AQUA);
+    }
+
+    // Highlight the face of each block the player last touched.
+    for (entity, transform, sprite) in &block_query {
+        let Some(side) = last_contacts.0.get(&entity) else {
+            continue;
+        };
+        let center = transform.translation.truncate();
+        let half = sprite.custom_size.unwrap_or_default() / 2.0;
+        let (start, end) = match side {
+            events::CollisionSide::Top => (
+                center + Vec2::new(-half.x, half.y),
+                center + Vec2::new(half.x, half.y),
+            ),
+            events::CollisionSide::Bottom => (
+                center + Vec2::new(-half.x, -half.y),
+                center + Vec2::new(half.x, -half.y),
+            ),
+            events::CollisionSide::Left => (
+                center + Vec2::new(-half.x, -half.y),
+                center + Vec2::new(-half.x, half.y),
+            ),
+            events::CollisionSide::Right => (
+                center + Vec2::new(half.x, -half.y),
+                center + Vec2::new(half.x, half.y),
+            ),
+        };
+        gizmos.line_2d(start, end, css::MAGENTA);
+        gizmos.circle_2d((start + end) / 2.0, 4.0, css::MAGENTA);
+    }
+
+    if let Ok((transform, velocity, player_state)) = player_query.get_single() {
+        let position = transform.translation.truncate();
+        let grounded_color = if player_state.grounded {
+            css::LIME
+        } else {
+            css::RED
+        };
+        gizmos.rect_2d(
+            position,
+            0.0,
+            Vec2::splat(config.objects.player.size),
+            grounded_color,
+        );
+        gizmos.arrow_2d(position, position + velocity.0 * VELOCITY_SCALE, css::AQUA);
+    }
+}
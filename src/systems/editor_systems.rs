@@ -0,0 +1,275 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::editor::{self, Drag, EditorState, Placement};
+use crate::resources::json_reader::HazardKind;
+use crate::states::GameState;
+use bevy::{color::palettes::css, prelude::*, window::PrimaryWindow};
+
+/// How much `-` and `=` change the selected block's or saw's speed.
+const SPEED_STEP: f32 = 25.0;
+/// How fast the arrow keys pan the camera, in pixels per second.
+const PAN_SPEED: f32 = 600.0;
+
+/// `F2` switches between playing and editing. Leaving the editor play-tests
+/// the level from the player start.
+pub fn toggle_editor_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    endless_run: Option<Res<resources::endless::EndlessRun>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Endless runs are generated on the fly, there is no level file to edit.
+    if !keyboard_input.just_pressed(KeyCode::F2) || endless_run.is_some() {
+        return;
+    }
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Editor),
+        GameState::Editor => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+/// Respawns the level from the config, on entering and leaving the editor and
+/// after every edit.
+pub fn editor_respawn_system(mut restart_events: EventWriter<events::RestartLevel>) {
+    restart_events.send(events::RestartLevel);
+}
+
+pub fn cursor_world_position(
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let cursor = window_query.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, cursor)
+}
+
+/// Clicking selects the topmost item under the cursor; dragging moves it, or
+/// resizes it while `Shift` is held.
+pub fn editor_mouse_system(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut editor_state: ResMut<EditorState>,
+    mut config: ResMut<resources::json_reader::Config>,
+) {
+    if mouse_input.just_released(MouseButton::Left) {
+        editor_state.drag = None;
+    }
+    let Some(cursor) = cursor_world_position(&window_query, &camera_query) else {
+        return;
+    };
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        let hit = editor::items(&config).into_iter().find_map(|item| {
+            editor::rect(&config, item)
+                .filter(|rect| rect.contains(cursor))
+                .map(|rect| (item, rect))
+        });
+        editor_state.selected = hit.map(|(item, _)| item);
+        editor_state.drag = hit.map(|(_, rect)| {
+            if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                Drag::Resize { anchor: rect.min }
+            } else {
+                Drag::Move {
+                    grab: cursor - rect.min,
+                }
+            }
+        });
+        return;
+    }
+
+    let (Some(item), Some(drag)) = (editor_state.selected, editor_state.drag) else {
+        return;
+    };
+    let Some(current) = editor::rect(&config, item) else {
+        return;
+    };
+    let target = match drag {
+        Drag::Move { grab } => {
+            let min = editor_state.snap(cursor - grab);
+            Rect::from_corners(min, min + current.size())
+        }
+        Drag::Resize { anchor } => {
+            let grid = if editor_state.snap {
+                editor_state.grid
+            } else {
+                1.0
+            };
+            let max = editor_state.snap(cursor).max(anchor + grid);
+            Rect::from_corners(anchor, max)
+        }
+    };
+    // Only write on change, so the level isn't respawned every frame.
+    if target != current {
+        editor::set_rect(&mut config, item, target);
+    }
+}
+
+/// Number keys place items at the cursor, `Delete` removes the selection, `G`
+/// toggles grid snapping, `-`/`=` and `F` set block and saw motion and
+/// `Ctrl+S` saves the level file.
+pub fn editor_keyboard_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut editor_state: ResMut<EditorState>,
+    mut config: ResMut<resources::json_reader::Config>,
+    path: Res<resources::json_reader::JsonFilePath>,
+) {
+    let placement = [
+        (KeyCode::Digit1, Placement::Block),
+        (KeyCode::Digit2, Placement::Hazard(HazardKind::Spikes)),
+        (KeyCode::Digit3, Placement::Hazard(HazardKind::Saw)),
+        (KeyCode::Digit4, Placement::Hazard(HazardKind::KillZone)),
+        (KeyCode::Digit5, Placement::Checkpoint),
+        (KeyCode::Digit6, Placement::Goal),
+        (KeyCode::Digit7, Placement::PlayerStart),
+        (KeyCode::Digit8, Placement::Wall),
+    ]
+    .into_iter()
+    .find(|(key, _)| keyboard_input.just_pressed(*key));
+    if let Some((_, placement)) = placement {
+        if let Some(cursor) = cursor_world_position(&window_query, &camera_query) {
+            let position = editor_state.snap(cursor);
+            editor_state.selected = Some(editor::place(&mut config, placement, position));
+        }
+    }
+
+    if keyboard_input.any_just_pressed([KeyCode::Delete, KeyCode::Backspace]) {
+        if let Some(item) = editor_state.selected {
+            if editor::remove(&mut config, item) {
+                editor_state.selected = None;
+                editor_state.drag = None;
+            }
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        editor_state.selected = None;
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyG) {
+        editor_state.snap = !editor_state.snap;
+    }
+
+    if let Some(item) = editor_state.selected {
+        let step = if keyboard_input.just_pressed(KeyCode::Equal) {
+            Some(SPEED_STEP)
+        } else if keyboard_input.just_pressed(KeyCode::Minus) {
+            Some(-SPEED_STEP)
+        } else {
+            None
+        };
+        let flip = keyboard_input.just_pressed(KeyCode::KeyF);
+        if step.is_some() || flip {
+            if let Some(speed) = editor::speed(&config, item) {
+                // Steps change the magnitude, `F` the starting direction.
+                let direction = if speed < 0.0 { -1.0 } else { 1.0 };
+                let magnitude = (speed.abs() + step.unwrap_or(0.0)).max(0.0);
+                let direction = if flip { -direction } else { direction };
+                editor::set_speed(&mut config, item, magnitude * direction);
+            }
+        }
+    }
+
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keyboard_input.just_pressed(KeyCode::KeyS) {
+        editor_state.status = Some(
+            match resources::json_reader::save_config(&config, &path.0) {
+                Ok(()) => format!("Saved {}", path.0),
+                Err(err) => format!("Unable to save {}: {err:#}", path.0),
+            },
+        );
+    }
+}
+
+/// The arrow keys pan the camera around levels larger than the screen.
+pub fn editor_pan_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut camera_query: Query<&mut components::FollowCamera>,
+    time: Res<Time>,
+) {
+    let direction = [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ]
+    .into_iter()
+    .filter(|(key, _)| keyboard_input.pressed(*key))
+    .map(|(_, direction)| direction)
+    .sum::<Vec2>();
+    for mut camera in &mut camera_query {
+        camera.position += direction * PAN_SPEED * time.delta_seconds();
+    }
+}
+
+/// Outlines every editable item, the selection and the snap grid.
+pub fn draw_editor_system(
+    mut gizmos: Gizmos,
+    editor_state: Res<EditorState>,
+    config: Res<resources::json_reader::Config>,
+) {
+    if editor_state.snap {
+        let bounds = config.bounds();
+        let grid_color = Color::srgba(1.0, 1.0, 1.0, 0.05);
+        let mut x = (bounds.min.x / editor_state.grid).ceil() * editor_state.grid;
+        while x <= bounds.max.x {
+            gizmos.line_2d(
+                Vec2::new(x, bounds.min.y),
+                Vec2::new(x, bounds.max.y),
+                grid_color,
+            );
+            x += editor_state.grid;
+        }
+        let mut y = (bounds.min.y / editor_state.grid).ceil() * editor_state.grid;
+        while y <= bounds.max.y {
+            gizmos.line_2d(
+                Vec2::new(bounds.min.x, y),
+                Vec2::new(bounds.max.x, y),
+                grid_color,
+            );
+            y += editor_state.grid;
+        }
+    }
+
+    for item in editor::items(&config) {
+        let Some(rect) = editor::rect(&config, item) else {
+            continue;
+        };
+        let color = if editor_state.selected == Some(item) {
+            css::YELLOW
+        } else {
+            css::GRAY
+        };
+        gizmos.rect_2d(rect.center(), 0.0, rect.size(), color);
+    }
+}
+
+pub fn editor_status_system(
+    editor_state: Res<EditorState>,
+    config: Res<resources::json_reader::Config>,
+    mut query: Query<&mut Text, With<components::EditorStatusText>>,
+) {
+    let selected = match editor_state.selected {
+        Some(item) => {
+            let speed = editor::speed(&config, item)
+                .map(|speed| format!(", speed {speed:.0}"))
+                .unwrap_or_default();
+            format!("{item:?}{speed}")
+        }
+        None => "nothing".to_string(),
+    };
+    let snap = if editor_state.snap {
+        format!("{:.0}", editor_state.grid)
+    } else {
+        "off".to_string()
+    };
+    for mut text in &mut query {
+        text.sections[0].value = format!(
+            "Selected: {selected}   Snap: {snap}   {}",
+            editor_state.status.as_deref().unwrap_or_default()
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::endless::{EndlessRun, FLOOR};
+use crate::spawners;
+use crate::states::GameState;
+use bevy::prelude::*;
+
+/// How far above the top of the screen platforms are generated.
+const LOOKAHEAD: f32 = 200.0;
+
+/// Swaps in the endless level and starts playing it.
+pub fn start_endless_system(
+    mut commands: Commands,
+    mut start_events: EventReader<events::StartEndless>,
+    mut config: ResMut<resources::json_reader::Config>,
+    mut restart_events: EventWriter<events::RestartLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if start_events.is_empty() {
+        return;
+    }
+    start_events.clear();
+
+    *config = EndlessRun::level(&config);
+    commands.insert_resource(EndlessRun::new(&config));
+    restart_events.send(events::RestartLevel);
+    next_state.set(GameState::Playing);
+}
+
+/// A restart during a run starts a fresh climb from the floor.
+pub fn restart_endless_system(
+    mut restart_events: EventReader<events::RestartLevel>,
+    run: Option<ResMut<EndlessRun>>,
+    config: Res<resources::json_reader::Config>,
+    mut camera_query: Query<&mut components::FollowCamera>,
+) {
+    if restart_events.is_empty() {
+        return;
+    }
+    restart_events.clear();
+    let Some(mut run) = run else {
+        return;
+    };
+
+    *run = EndlessRun::new(&config);
+    for mut camera in &mut camera_query {
+        camera.position = Vec2::ZERO;
+    }
+}
+
+/// Ends the run when leaving for the main menu.
+pub fn stop_endless_system(
+    mut commands: Commands,
+    query: Query<Entity, With<components::EndlessEntity>>,
+    mut camera_query: Query<&mut components::FollowCamera>,
+) {
+    commands.remove_resource::<EndlessRun>();
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+    for mut camera in &mut camera_query {
+        camera.position = Vec2::ZERO;
+    }
+}
+
+/// Keeps platforms generated up to a little above the top of the screen.
+pub fn spawn_pads_system(
+    mut commands: Commands,
+    mut run: ResMut<EndlessRun>,
+    config: Res<resources::json_reader::Config>,
+    theme: Res<resources::theme::Theme>,
+    camera_query: Query<(&components::FollowCamera, &OrthographicProjection)>,
+) {
+    let Ok((camera, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let screen_top = camera.position.y + projection.area.max.y;
+    while run.generator.surface() < screen_top + LOOKAHEAD {
+        run.generator.difficulty = run.difficulty();
+        let Some(block) = run.generator.next() else {
+            break;
+        };
+        // Past the level's own blocks, so indices stay unique.
+        let index = config.objects.blocks.len() + run.pads;
+        spawners::blocks::spawn_block(&mut commands, index, &block, theme.pad_color(&config));
+        run.pads += 1;
+    }
+}
+
+/// Despawns platforms that have scrolled off the bottom of the screen.
+pub fn despawn_pads_system(
+    mut commands: Commands,
+    block_query: Query<(Entity, &Transform, &components::BlockSize)>,
+    camera_query: Query<(&components::FollowCamera, &OrthographicProjection)>,
+) {
+    let Ok((camera, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let screen_bottom = camera.position.y + projection.area.min.y;
+    for (entity, transform, size) in &block_query {
+        if transform.translation.y + size.0.y / 2.0 < screen_bottom {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Scrolls the camera up with the player, but never back down. The side
+/// walls move along so they always fill the screen. Replaces the follow
+/// camera during a run.
+pub fn endless_camera_system(
+    player_query: Query<&Transform, With<components::Player>>,
+    mut camera_query: Query<&mut components::FollowCamera>,
+    mut wall_query: Query<(&mut Transform, &components::Wall), Without<components::Player>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+    camera.position.y = camera.position.y.max(player_transform.translation.y);
+    for (mut transform, wall) in &mut wall_query {
+        if wall.index != FLOOR {
+            transform.translation.y = camera.position.y;
+        }
+    }
+}
+
+pub fn endless_height_system(
+    mut run: ResMut<EndlessRun>,
+    player_query: Query<&Transform, With<components::Player>>,
+    mut text_query: Query<&mut Text, With<components::HeightText>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let height = player_transform.translation.y - run.start_y;
+    if height > run.height {
+        run.height = height;
+    }
+    for mut text in &mut text_query {
+        text.sections[1].value = format!("{:.0}", run.height);
+    }
+}
+
+/// Ends the run once the player falls below the bottom of the screen, and
+/// records its height.
+#[allow(clippy::too_many_arguments)]
+pub fn fall_out_system(
+    mut run: ResMut<EndlessRun>,
+    player_query: Query<&Transform, With<components::Player>>,
+    camera_query: Query<(&components::FollowCamera, &OrthographicProjection)>,
+    config: Res<resources::json_reader::Config>,
+    mut save_data: ResMut<resources::save_data::SaveData>,
+    save_path: Res<resources::save_data::SavePath>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let (Ok(player_transform), Ok((camera, projection))) =
+        (player_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    let screen_bottom = camera.position.y + projection.area.min.y;
+    if player_transform.translation.y + config.objects.player.size / 2.0 >= screen_bottom {
+        return;
+    }
+
+    run.rank = save_data.record_endless(run.height as u32);
+    if let Err(err) = save_data.write(&save_path.0) {
+        error!("Unable to write save {}: {err:#}", save_path.0.display());
+    }
+    next_state.set(GameState::RunOver);
+}
@@ -1,19 +1,48 @@
 use crate::components;
+use crate::events;
+use crate::resources;
 use bevy::prelude::*;
 
+fn is_unlocked(goal: &components::Goal, score: &resources::level_state::Score) -> bool {
+    !goal.requires_all_collectibles || score.collected >= score.total
+}
+
 pub fn goal_system(
-    mut player_query: Query<(&Transform, &components::PlayerState), With<components::Player>>,
-    goal_query: Query<(&Transform, &components::Goal)>,
-    // mut game_state: ResMut<crate::resources::GameState>,
+    mut contact_events: EventReader<events::SensorContact>,
+    goal_query: Query<&components::Goal>,
+    score: Res<resources::level_state::Score>,
+    mut completed: ResMut<resources::level_state::LevelCompleted>,
+    mut goal_events: EventWriter<events::GoalReached>,
 ) {
-    let (player_transform, _) = player_query.single_mut();
-    let (goal_transform, _) = goal_query.single();
+    if completed.0 {
+        contact_events.clear();
+        return;
+    }
+
+    let reached = contact_events.read().find(|contact| {
+        goal_query
+            .get(contact.entity)
+            .is_ok_and(|goal| is_unlocked(goal, &score))
+    });
+    if let Some(contact) = reached {
+        info!("Goal reached!");
+        completed.0 = true;
+        goal_events.send(events::GoalReached {
+            goal: contact.entity,
+        });
+    }
+}
 
-    if player_transform
-        .translation
-        .distance(goal_transform.translation)
-        < 10.0
-    {
-        println!("Goal reached!");
+pub fn goal_lock_system(
+    score: Res<resources::level_state::Score>,
+    mut goal_query: Query<(&components::Goal, &mut Sprite)>,
+    config: Res<resources::json_reader::Config>,
+    theme: Res<resources::theme::Theme>,
+) {
+    if !score.is_changed() {
+        return;
+    }
+    for (goal, mut sprite) in &mut goal_query {
+        sprite.color = theme.goal_color(&config, !is_unlocked(goal, &score));
     }
 }
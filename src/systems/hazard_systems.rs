@@ -0,0 +1,75 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use bevy::prelude::*;
+
+pub fn hazard_contact_system(
+    mut contact_events: EventReader<events::SensorContact>,
+    hazard_query: Query<(), With<components::Hazard>>,
+    player_query: Query<&Transform, With<components::Player>>,
+    mut death_events: EventWriter<events::PlayerDied>,
+) {
+    if contact_events
+        .read()
+        .any(|contact| hazard_query.contains(contact.entity))
+    {
+        let Ok(transform) = player_query.get_single() else {
+            return;
+        };
+        death_events.send(events::PlayerDied {
+            position: transform.translation.truncate(),
+        });
+    }
+}
+
+pub fn respawn_system(
+    mut death_events: EventReader<events::PlayerDied>,
+    mut player_query: Query<
+        (
+            &mut Transform,
+            &mut components::Velocity,
+            &mut components::PlayerState,
+        ),
+        With<components::Player>,
+    >,
+    respawn_point: Res<resources::level_state::RespawnPoint>,
+    mut death_count: ResMut<resources::level_state::DeathCount>,
+) {
+    if death_events.is_empty() {
+        return;
+    }
+    death_events.clear();
+
+    let Ok((mut transform, mut velocity, mut player_state)) = player_query.get_single_mut() else {
+        return;
+    };
+    transform.translation = respawn_point.0.extend(transform.translation.z);
+    velocity.0 = Vec2::ZERO;
+    player_state.grounded = false;
+    death_count.0 += 1;
+}
+
+pub fn move_saw_system(
+    mut saw_query: Query<
+        (
+            &Transform,
+            &mut components::Direction,
+            &mut components::Velocity,
+            &components::Sensor,
+        ),
+        With<components::Hazard>,
+    >,
+    config: Res<resources::json_reader::Config>,
+) {
+    let inner_half_width = config.wall_params.thickness / 2.0;
+    for (transform, mut direction, mut velocity, sensor) in &mut saw_query {
+        let right_bound = config.wall_params.right_x - inner_half_width - sensor.0.x / 2.0;
+        let left_bound = config.wall_params.left_x + inner_half_width + sensor.0.x / 2.0;
+        if (direction.0 > 0.0 && transform.translation.x >= right_bound)
+            || (direction.0 < 0.0 && transform.translation.x <= left_bound)
+        {
+            direction.0 = -direction.0;
+            velocity.x = -velocity.x;
+        }
+    }
+}
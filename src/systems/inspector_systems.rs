@@ -0,0 +1,88 @@
+use crate::components;
+use crate::resources;
+use crate::resources::inspector::InspectorPanel;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+
+pub fn toggle_inspector_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut panel: ResMut<InspectorPanel>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F1) {
+        panel.open = !panel.open;
+    }
+}
+
+/// Sliders for the physics and player parameters of the loaded level, plus a
+/// button writing them back to the level file.
+pub fn inspector_ui_system(
+    mut contexts: EguiContexts,
+    mut config: ResMut<resources::json_reader::Config>,
+    path: Res<resources::json_reader::JsonFilePath>,
+    endless_run: Option<Res<resources::endless::EndlessRun>>,
+    mut panel: ResMut<InspectorPanel>,
+) {
+    if !panel.open {
+        return;
+    }
+
+    let mut gravity = config.physics.gravity;
+    let mut speed = config.objects.player.speed;
+    let mut mass = config.objects.player.mass;
+    let mut jump_force = config.objects.player.jump_force;
+    let mut save = false;
+
+    egui::Window::new("Inspector").show(contexts.ctx_mut(), |ui| {
+        ui.heading("Physics");
+        ui.add(egui::Slider::new(&mut gravity, 0.0..=50.0).text("gravity"));
+        ui.heading("Player");
+        ui.add(egui::Slider::new(&mut speed, 0.0..=1000.0).text("speed"));
+        ui.add(egui::Slider::new(&mut mass, 1.0..=100.0).text("mass"));
+        ui.add(egui::Slider::new(&mut jump_force, 0.0..=600.0).text("jump_force"));
+        ui.separator();
+        // The endless level is generated, saving it would overwrite the level
+        // file it replaced.
+        if endless_run.is_some() {
+            ui.label("Endless runs can't be saved");
+        } else {
+            save = ui.button("Save to JSON").clicked();
+        }
+        if let Some(status) = &panel.status {
+            ui.label(status);
+        }
+    });
+
+    // Only write back on change, so `Config` change detection stays meaningful.
+    if gravity != config.physics.gravity {
+        config.physics.gravity = gravity;
+    }
+    if speed != config.objects.player.speed {
+        config.objects.player.speed = speed;
+    }
+    if mass != config.objects.player.mass {
+        config.objects.player.mass = mass;
+    }
+    if jump_force != config.objects.player.jump_force {
+        config.objects.player.jump_force = jump_force;
+    }
+
+    if save {
+        panel.status = Some(
+            match resources::json_reader::save_config(&config, &path.0) {
+                Ok(()) => format!("Saved {}", path.0),
+                Err(err) => format!("Unable to save {}: {err:#}", path.0),
+            },
+        );
+    }
+}
+
+/// The player's `Mass` is copied from the config at spawn, so keep it in sync
+/// with live edits.
+pub fn sync_player_mass_system(
+    config: Res<resources::json_reader::Config>,
+    mut query: Query<&mut components::Mass, With<components::Player>>,
+) {
+    for mut mass in &mut query {
+        mass.0 = config.objects.player.mass;
+    }
+}
@@ -0,0 +1,230 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::key_bindings::{Action, KeyBindings};
+use crate::spawners;
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+    sprite::Mesh2dHandle,
+    utils::HashMap,
+};
+
+pub fn restart_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut restart_events: EventWriter<events::RestartLevel>,
+) {
+    if key_bindings.just_pressed(Action::Restart, &keyboard_input) {
+        restart_events.send(events::RestartLevel);
+    }
+}
+
+/// Respawns the level from scratch, which also resets checkpoints, the
+/// respawn point and the death counter.
+pub fn restart_level_system(
+    mut commands: Commands,
+    mut restart_events: EventReader<events::RestartLevel>,
+    level_query: Query<Entity, With<components::LevelEntity>>,
+    config: Res<resources::json_reader::Config>,
+    theme: Res<resources::theme::Theme>,
+    mut death_count: ResMut<resources::level_state::DeathCount>,
+) {
+    if restart_events.is_empty() {
+        return;
+    }
+    restart_events.clear();
+
+    for entity in &level_query {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawners::spawn_level(&mut commands, &config, &theme);
+    death_count.0 = 0;
+}
+
+pub fn load_level_system(
+    mut load_events: EventReader<events::LoadLevel>,
+    mut config: ResMut<resources::json_reader::Config>,
+    mut path: ResMut<resources::json_reader::JsonFilePath>,
+    mut restart_events: EventWriter<events::RestartLevel>,
+) {
+    let Some(load) = load_events.read().last() else {
+        return;
+    };
+    match resources::json_reader::load_config(&load.path) {
+        Ok(new_config) => {
+            *config = new_config;
+            path.0 = load.path.clone();
+            restart_events.send(events::RestartLevel);
+        }
+        Err(err) => error!("Unable to load level {}: {err:#}", load.path),
+    }
+}
+
+/// Switches to the theme the level names, or the default theme, unless it is
+/// already in use. Runs before the level is respawned so the new level is
+/// drawn with it.
+pub fn load_theme_system(
+    mut commands: Commands,
+    config: Res<resources::json_reader::Config>,
+    default_path: Res<resources::theme::DefaultThemePath>,
+    mut theme: ResMut<resources::theme::Theme>,
+) {
+    let path = config.theme.as_ref().or(default_path.0.as_ref());
+    if theme.path.as_ref() == path {
+        return;
+    }
+    *theme = match path {
+        Some(path) => resources::theme::Theme::load(path).unwrap_or_else(|err| {
+            error!("Unable to load theme {path}: {err:#}");
+            // Remember the path so a broken theme isn't read again every frame.
+            resources::theme::Theme {
+                path: Some(path.clone()),
+                ..default()
+            }
+        }),
+        None => default(),
+    };
+    commands.insert_resource(ClearColor(Color::srgb_from_array(theme.background)));
+}
+
+/// Gives newly spawned players, blocks, goals and rectangular walls the
+/// texture the level's `sprites` set for them. Their spawners have already
+/// sized and tinted them.
+#[allow(clippy::type_complexity)]
+pub fn texture_sprites_system(
+    mut commands: Commands,
+    sprite_query: Query<
+        (
+            Entity,
+            Has<components::Player>,
+            Has<components::Block>,
+            Has<components::Goal>,
+            Option<&components::Wall>,
+        ),
+        Or<(
+            Added<components::Player>,
+            Added<components::Block>,
+            Added<components::Goal>,
+            Added<components::Wall>,
+        )>,
+    >,
+    config: Res<resources::json_reader::Config>,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut layout_cache: Local<HashMap<resources::json_reader::Atlas, Handle<TextureAtlasLayout>>>,
+) {
+    let sprites = &config.sprites;
+    let walls = config.walls();
+    for (entity, is_player, is_block, is_goal, wall) in &sprite_query {
+        let texture = if is_player {
+            &sprites.player
+        } else if is_block {
+            &sprites.block
+        } else if is_goal {
+            &sprites.goal
+        } else if wall.is_some_and(|wall| {
+            matches!(
+                walls.get(wall.index),
+                Some(resources::json_reader::Wall::Rect { .. })
+            )
+        }) {
+            &sprites.wall
+        } else {
+            continue;
+        };
+        let Some(texture) = texture else {
+            continue;
+        };
+
+        let mut entity = commands.entity(entity);
+        entity.insert(asset_server.load::<Image>(&texture.texture));
+        if let Some(atlas) = texture.atlas {
+            let layout = layout_cache
+                .entry(atlas)
+                .or_insert_with(|| layouts.add(atlas.layout()))
+                .clone();
+            entity.insert(TextureAtlas {
+                layout,
+                index: atlas.index,
+            });
+        }
+        if let Some(scale_mode) = texture.scale_mode.image_scale_mode() {
+            entity.insert(scale_mode);
+        }
+    }
+}
+
+/// Gives newly spawned polygon walls a mesh to draw them with; rectangular
+/// walls are plain sprites. A wall texture is stretched over the polygon's
+/// bounds.
+pub fn mesh_polygon_walls_system(
+    mut commands: Commands,
+    wall_query: Query<(Entity, &components::Wall), Added<components::Wall>>,
+    config: Res<resources::json_reader::Config>,
+    theme: Res<resources::theme::Theme>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let walls = config.walls();
+    for (entity, wall) in &wall_query {
+        if !matches!(
+            walls.get(wall.index),
+            Some(resources::json_reader::Wall::Polygon { .. })
+        ) {
+            continue;
+        }
+        let min = wall
+            .points
+            .iter()
+            .fold(Vec2::INFINITY, |min, point| min.min(*point));
+        let max = wall
+            .points
+            .iter()
+            .fold(Vec2::NEG_INFINITY, |max, point| max.max(*point));
+        let bounds = Rect::from_corners(min, max);
+        let uvs: Vec<[f32; 2]> = wall
+            .points
+            .iter()
+            .map(|point| {
+                let uv = (*point - bounds.min) / bounds.size().max(Vec2::splat(f32::EPSILON));
+                [uv.x, 1.0 - uv.y]
+            })
+            .collect();
+        let positions: Vec<[f32; 3]> = wall
+            .points
+            .iter()
+            .map(|point| point.extend(0.0).into())
+            .collect();
+        // The points are convex and counterclockwise, so a fan covers them.
+        let indices = (1..wall.points.len().saturating_sub(1) as u32)
+            .flat_map(|i| [0, i, i + 1])
+            .collect();
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_indices(Indices::U32(indices))
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            vec![[0.0, 0.0, 1.0]; positions.len()],
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        let material = ColorMaterial {
+            color: theme.wall_color(&config),
+            texture: config
+                .sprites
+                .wall
+                .as_ref()
+                .map(|texture| asset_server.load(&texture.texture)),
+        };
+        commands
+            .entity(entity)
+            .insert((Mesh2dHandle(meshes.add(mesh)), materials.add(material)));
+    }
+}
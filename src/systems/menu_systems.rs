@@ -0,0 +1,94 @@
+use crate::components::MenuButton;
+use crate::events;
+use crate::resources;
+use crate::resources::key_bindings::{Action, KeyBindings};
+use crate::resources::theme::Theme;
+use crate::states::GameState;
+use bevy::{app::AppExit, prelude::*};
+
+#[allow(clippy::type_complexity)]
+pub fn button_color_system(
+    theme: Res<Theme>,
+    mut query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut color) in &mut query {
+        *color = theme.button_color(*interaction).into();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn menu_action_system(
+    query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    level_list: Res<resources::level_list::LevelList>,
+    save_data: Res<resources::save_data::SaveData>,
+    mut load_events: EventWriter<events::LoadLevel>,
+    mut restart_events: EventWriter<events::RestartLevel>,
+    mut endless_events: EventWriter<events::StartEndless>,
+    mut exit_events: EventWriter<AppExit>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Play => {
+                // Continue from the furthest level unlocked so far.
+                let level = level_list
+                    .levels
+                    .iter()
+                    .rev()
+                    .find(|level| save_data.is_unlocked(&level.path))
+                    .or(level_list.levels.first());
+                if let Some(level) = level {
+                    load_events.send(events::LoadLevel {
+                        path: level.path.clone(),
+                    });
+                }
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::LevelSelect => next_state.set(GameState::LevelSelect),
+            MenuButton::Settings => next_state.set(GameState::Settings),
+            MenuButton::Quit => {
+                exit_events.send(AppExit::Success);
+            }
+            MenuButton::Back | MenuButton::QuitToMenu => next_state.set(GameState::MainMenu),
+            MenuButton::PlayLevel(index) => {
+                if let Some(level) = level_list.levels.get(*index) {
+                    load_events.send(events::LoadLevel {
+                        path: level.path.clone(),
+                    });
+                    next_state.set(GameState::Playing);
+                }
+            }
+            MenuButton::Endless => {
+                endless_events.send(events::StartEndless);
+            }
+            MenuButton::Resume => next_state.set(GameState::Playing),
+            MenuButton::Restart => {
+                restart_events.send(events::RestartLevel);
+                next_state.set(GameState::Playing);
+            }
+        }
+    }
+}
+
+/// The pause key pauses and resumes the game; `Escape` also resumes and leaves
+/// the level select, settings and endless run over screens.
+pub fn menu_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pause = key_bindings.just_pressed(Action::Pause, &keyboard_input);
+    let escape = keyboard_input.just_pressed(KeyCode::Escape);
+    match state.get() {
+        GameState::Playing if pause => next_state.set(GameState::Paused),
+        GameState::Paused if pause || escape => next_state.set(GameState::Playing),
+        GameState::LevelSelect | GameState::Settings | GameState::RunOver if escape => {
+            next_state.set(GameState::MainMenu)
+        }
+        _ => {}
+    }
+}
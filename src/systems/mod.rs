@@ -1,6 +1,24 @@
+pub mod animation_systems;
 pub mod block_systems;
 pub mod blockbundle_systems;
+pub mod camera_systems;
+pub mod checkpoint_systems;
+pub mod collectible_systems;
+pub mod debug_systems;
+pub mod editor_systems;
+pub mod endless_systems;
 pub mod goal_systems;
+pub mod hazard_systems;
+#[cfg(feature = "inspector")]
+pub mod inspector_systems;
+pub mod level_systems;
+pub mod menu_systems;
+pub mod parallax_systems;
+pub mod particle_systems;
 pub mod physics;
 pub mod player_systems;
+pub mod results_systems;
+pub mod save_systems;
+pub mod settings_systems;
+pub mod sound_systems;
 pub mod ui_systems;
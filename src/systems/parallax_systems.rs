@@ -0,0 +1,86 @@
+use crate::components;
+use crate::resources;
+use bevy::prelude::*;
+
+/// Gives newly spawned backgrounds their image, tiled along the axes they
+/// repeat on.
+pub fn texture_backgrounds_system(
+    mut commands: Commands,
+    layer_query: Query<(Entity, &components::ParallaxLayer), Added<components::ParallaxLayer>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, layer) in &layer_query {
+        let background = &layer.background;
+        let mut entity = commands.entity(entity);
+        entity.insert(asset_server.load::<Image>(&background.texture));
+        if background.repeat_x || background.repeat_y {
+            entity.insert(ImageScaleMode::Tiled {
+                tile_x: background.repeat_x,
+                tile_y: background.repeat_y,
+                stretch_value: background.scale,
+            });
+        }
+    }
+}
+
+/// Moves each background with the camera by its parallax factors and its
+/// auto-scroll. Repeating backgrounds are sized to cover the view with whole
+/// copies of their image and wrapped around the camera.
+#[allow(clippy::type_complexity)]
+pub fn parallax_system(
+    time: Res<Time>,
+    config: Res<resources::json_reader::Config>,
+    images: Res<Assets<Image>>,
+    camera_query: Query<
+        (&Transform, &OrthographicProjection),
+        (
+            With<components::FollowCamera>,
+            Without<components::ParallaxLayer>,
+        ),
+    >,
+    mut layer_query: Query<(
+        &mut components::ParallaxLayer,
+        &mut Transform,
+        &mut Sprite,
+        &Handle<Image>,
+    )>,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let camera = camera_transform.translation.truncate();
+    let view = projection.area.size();
+    let center = config.bounds().center();
+
+    for (mut layer, mut transform, mut sprite, image) in &mut layer_query {
+        let scroll = Vec2::from(layer.background.scroll) * time.delta_seconds();
+        layer.scrolled += scroll;
+        // Not loaded yet, so its size is unknown.
+        let Some(image) = images.get(image) else {
+            continue;
+        };
+        let background = &layer.background;
+        let tile = image.size_f32() * background.scale;
+        let parallax = Vec2::from(background.parallax);
+        let position = center
+            + Vec2::from(background.offset)
+            + (camera - center) * (Vec2::ONE - parallax)
+            + layer.scrolled;
+
+        let repeat = BVec2::new(background.repeat_x, background.repeat_y);
+        // Tiles are laid out from the sprite's corner. An even number of
+        // them puts a seam at the sprite's center too, so moving it by whole
+        // tiles to stay on screen doesn't show.
+        let copies = ((view / tile + 2.0) / 2.0).ceil() * 2.0;
+        let size = Vec2::select(repeat, tile * copies, tile);
+        let position = Vec2::select(
+            repeat,
+            camera + (position - camera).rem_euclid(tile),
+            position,
+        );
+        transform.translation = position.extend(transform.translation.z);
+        if sprite.custom_size != Some(size) {
+            sprite.custom_size = Some(size);
+        }
+    }
+}
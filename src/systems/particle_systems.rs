@@ -0,0 +1,179 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::particles::{Emitter, ParticleEffects, ParticlePool};
+use crate::spawners::PARTICLE_Z;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+/// Spawns the hidden particles every effect draws from.
+pub fn spawn_particle_pool(
+    mut commands: Commands,
+    effects: Res<ParticleEffects>,
+    mut pool: ResMut<ParticlePool>,
+) {
+    let particles = (0..effects.pool_size).map(|_| {
+        commands
+            .spawn((
+                SpriteBundle {
+                    transform: Transform::from_xyz(0.0, 0.0, PARTICLE_Z),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                components::Particle::default(),
+            ))
+            .id()
+    });
+    pool.free.extend(particles);
+}
+
+type ParticleQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut components::Particle,
+        &'static mut Transform,
+        &'static mut Sprite,
+        &'static mut Visibility,
+    ),
+>;
+
+/// Bursts of particles for jumps, landings, deaths and goals.
+#[allow(clippy::too_many_arguments)]
+pub fn emit_particles_system(
+    mut jump_events: EventReader<events::PlayerJumped>,
+    mut landed_events: EventReader<events::PlayerLanded>,
+    mut death_events: EventReader<events::PlayerDied>,
+    mut goal_events: EventReader<events::GoalReached>,
+    player_query: Query<&Transform, (With<components::Player>, Without<components::Particle>)>,
+    goal_query: Query<&Transform, (With<components::Goal>, Without<components::Particle>)>,
+    config: Res<resources::json_reader::Config>,
+    effects: Res<ParticleEffects>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: ParticleQuery,
+) {
+    let feet = player_query.get_single().ok().map(|transform| {
+        transform.translation.truncate() - Vec2::new(0.0, config.objects.player.size / 2.0)
+    });
+    let mut emit = |emitter: &Emitter, origin: Vec2| {
+        for _ in 0..emitter.count {
+            let Some(entity) = pool.free.pop() else {
+                return;
+            };
+            if let Ok(particle) = particle_query.get_mut(entity) {
+                launch(particle, emitter, origin);
+            }
+        }
+    };
+
+    for _ in jump_events.read() {
+        if let Some(feet) = feet {
+            emit(&effects.jump, feet);
+        }
+    }
+    for _ in landed_events.read() {
+        if let Some(feet) = feet {
+            emit(&effects.land, feet);
+        }
+    }
+    for death in death_events.read() {
+        emit(&effects.death, death.position);
+    }
+    for reached in goal_events.read() {
+        if let Ok(transform) = goal_query.get(reached.goal) {
+            emit(&effects.goal, transform.translation.truncate());
+        }
+    }
+}
+
+/// Starts a pooled particle off from `origin` as `emitter` describes.
+fn launch(
+    (mut particle, mut transform, mut sprite, mut visibility): (
+        Mut<components::Particle>,
+        Mut<Transform>,
+        Mut<Sprite>,
+        Mut<Visibility>,
+    ),
+    emitter: &Emitter,
+    origin: Vec2,
+) {
+    let between = |[min, max]: [f32; 2]| min + (max - min) * fastrand::f32();
+    let angle = (emitter.angle + emitter.spread * (fastrand::f32() * 2.0 - 1.0)) * PI / 180.0;
+    let [red, green, blue, alpha] = match emitter.colors.len() {
+        0 => [1.0; 4],
+        len => emitter.colors[fastrand::usize(..len)],
+    };
+    let [radius_x, radius_y] = emitter.radius;
+    let offset = Vec2::new(
+        radius_x * (fastrand::f32() * 2.0 - 1.0),
+        radius_y * (fastrand::f32() * 2.0 - 1.0),
+    );
+
+    *particle = components::Particle {
+        velocity: Vec2::from_angle(angle) * between(emitter.speed),
+        age: 0.0,
+        lifetime: between(emitter.lifetime),
+        gravity: emitter.gravity,
+        drag: emitter.drag,
+        size: emitter.size,
+        color: Color::srgba(red, green, blue, alpha),
+        fade: emitter.fade,
+    };
+    transform.translation = (origin + offset).extend(PARTICLE_Z);
+    sprite.color = particle.color;
+    sprite.custom_size = Some(Vec2::splat(emitter.size[0]));
+    *visibility = Visibility::Inherited;
+}
+
+/// Moves, shrinks and fades live particles, and returns expired ones to the
+/// pool.
+pub fn update_particles_system(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(
+        Entity,
+        &mut components::Particle,
+        &mut Transform,
+        &mut Sprite,
+        &mut Visibility,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite, mut visibility) in &mut particle_query {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+        let slowdown = (1.0 - particle.drag * delta).max(0.0);
+        let velocity = (particle.velocity - Vec2::new(0.0, particle.gravity * delta)) * slowdown;
+        particle.velocity = velocity;
+        transform.translation += (velocity * delta).extend(0.0);
+
+        let life = particle.age / particle.lifetime;
+        let [start, end] = particle.size;
+        sprite.custom_size = Some(Vec2::splat(start + (end - start) * life));
+        if particle.fade {
+            sprite.color = particle
+                .color
+                .with_alpha(particle.color.alpha() * (1.0 - life));
+        }
+    }
+}
+
+/// Hides every live particle, e.g. when leaving the level.
+pub fn clear_particles_system(
+    mut pool: ResMut<ParticlePool>,
+    mut particle_query: Query<(Entity, &mut Visibility), With<components::Particle>>,
+) {
+    for (entity, mut visibility) in &mut particle_query {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use crate::components;
 use crate::events;
 use crate::resources;
-use bevy::{math::bounding::Aabb2d, prelude::*};
+use bevy::{
+    math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume},
+    prelude::*,
+};
 
 pub fn apply_velocity(mut query: Query<(&mut Transform, &components::Velocity)>, time: Res<Time>) {
     for (mut transform, velocity) in &mut query {
@@ -22,19 +25,25 @@ pub fn apply_gravity(
     time: Res<Time>,
     config: Res<resources::json_reader::Config>,
 ) {
-    let (mut velocity, mass, player_state) = query.single_mut();
-    if player_state.grounded == false {
+    let Ok((mut velocity, mass, player_state)) = query.get_single_mut() else {
+        return;
+    };
+    if !player_state.grounded {
         velocity.y -= config.physics.gravity * mass.0 * time.delta_seconds();
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn detect_collision_system(
     mut player_query: Query<(&Transform, &mut components::PlayerState), With<components::Player>>,
-    block_query: Query<(&components::Collider, &Transform, &components::Block)>,
+    block_query: Query<(Entity, &Transform, &components::BlockSize), With<components::Collider>>,
+    wall_query: Query<(Entity, &Transform, &components::Wall), With<components::Collider>>,
     config: Res<resources::json_reader::Config>,
     mut collision_events: EventWriter<events::Collision>,
 ) {
-    let (player_transform, mut player_state) = player_query.single_mut();
+    let Ok((player_transform, mut player_state)) = player_query.get_single_mut() else {
+        return;
+    };
 
     let player_aabb = Aabb2d::new(
         player_transform.translation.truncate(),
@@ -44,124 +53,198 @@ pub fn detect_collision_system(
         ),
     );
 
-    if let Some(collision) = detect_collision(&player_aabb, block_query, &config) {
-        if collision.side == events::CollisionSide::Top {
-            player_state.grounded = true;
+    let blocks = block_query.iter().map(|(entity, transform, size)| {
+        let center = transform.translation.truncate();
+        let half = size.0 / 2.0;
+        let points = vec![
+            center - half,
+            center + Vec2::new(half.x, -half.y),
+            center + half,
+            center + Vec2::new(-half.x, half.y),
+        ];
+        (entity, points)
+    });
+    let walls = wall_query.iter().map(|(entity, transform, wall)| {
+        let center = transform.translation.truncate();
+        (
+            entity,
+            wall.points.iter().map(|point| center + *point).collect(),
+        )
+    });
+
+    player_state.grounded = false;
+    player_state.wall_side = 0.0;
+    for (entity, points) in blocks.chain(walls) {
+        if let Some((side, push)) = separate(&player_aabb, &points) {
+            match side {
+                events::CollisionSide::Top => player_state.grounded = true,
+                // Running into a wall's left side means the wall is on the right.
+                events::CollisionSide::Left => player_state.wall_side = 1.0,
+                events::CollisionSide::Right => player_state.wall_side = -1.0,
+                events::CollisionSide::Bottom => {}
+            }
+            collision_events.send(events::Collision { entity, side, push });
         }
-        collision_events.send(collision);
-    } else {
-        player_state.grounded = false;
     }
 }
 
-pub fn handle_collision_system(
-    mut query_set: ParamSet<(
-        Query<
-            (
-                &mut Transform,
-                &mut components::Velocity,
-                &mut components::PlayerState,
-            ),
-            With<components::Player>,
-        >,
-        Query<(&components::Block, &Transform)>,
-    )>,
-    mut collision_events: EventReader<events::Collision>,
+pub fn detect_sensor_system(
+    player_query: Query<&Transform, With<components::Player>>,
+    sensor_query: Query<(Entity, &Transform, &components::Sensor)>,
     config: Res<resources::json_reader::Config>,
+    mut contact_events: EventWriter<events::SensorContact>,
 ) {
-    let mut collisions_to_handle = Vec::new();
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_aabb = Aabb2d::new(
+        player_transform.translation.truncate(),
+        Vec2::splat(config.objects.player.size / 2.0),
+    );
 
-    for collision in collision_events.read() {
-        let block = config.objects.blocks.get(collision.block_index).unwrap();
-        if let Some((_, block_transform)) = query_set
-            .p1()
-            .iter()
-            .find(|(b, _)| b.0 == collision.block_index)
-        {
-            collisions_to_handle.push((collision.side, block.clone(), block_transform.translation));
+    for (entity, sensor_transform, sensor) in &sensor_query {
+        let sensor_aabb = Aabb2d::new(sensor_transform.translation.truncate(), sensor.0 / 2.0);
+        if player_aabb.intersects(&sensor_aabb) {
+            contact_events.send(events::SensorContact { entity });
         }
     }
+}
 
-    if let Ok((mut player_transform, mut player_velocity, mut player_state)) =
-        query_set.p0().get_single_mut()
-    {
-        for (side, block, block_translation) in collisions_to_handle {
-            match side {
-                events::CollisionSide::Left => {
-                    player_velocity.x = 0.0;
-                    player_transform.translation.x =
-                        block_translation.x - block.w / 2.0 - config.objects.player.size / 2.0;
-                }
-                events::CollisionSide::Right => {
-                    player_velocity.x = 0.0;
-                    player_transform.translation.x =
-                        block_translation.x + block.w / 2.0 + config.objects.player.size / 2.0;
-                }
-                events::CollisionSide::Top => {
-                    player_velocity.y = 0.0;
-                    player_transform.translation.y =
-                        block_translation.y + block.h / 2.0 + config.objects.player.size / 2.0;
-                }
-                events::CollisionSide::Bottom => {
-                    player_velocity.y = 0.0;
-                    player_transform.translation.y =
-                        block_translation.y - block.h / 2.0 - config.objects.player.size / 2.0;
-                }
-            }
-        }
-    }
+/// How far apart the player and a block or wall can be and still touch.
+/// Without it a player resting on the floor would only touch it every other
+/// tick, after gravity pulled them back in.
+const CONTACT_SKIN: f32 = 0.1;
 
-    collision_events.clear();
-}
+/// Pushes the player out of everything they ran into and stops them moving
+/// into it.
+pub fn handle_collision_system(
+    mut player_query: Query<(&mut Transform, &mut components::Velocity), With<components::Player>>,
+    mut collision_events: EventReader<events::Collision>,
+) {
+    let Ok((mut player_transform, mut player_velocity)) = player_query.get_single_mut() else {
+        collision_events.clear();
+        return;
+    };
 
-fn detect_collision(
-    player_aabb: &Aabb2d,
-    block_query: Query<(&components::Collider, &Transform, &components::Block)>,
-    config: &resources::json_reader::Config,
-) -> Option<events::Collision> {
-    for ((_, block_transform, block_index), block_config) in
-        block_query.iter().zip(config.objects.blocks.iter())
-    {
-        let block_size = Vec2::new(block_config.w / 2.0, block_config.h / 2.0);
-        let block_aabb = Aabb2d::new(block_transform.translation.truncate(), block_size);
-
-        if overlap(&player_aabb, &block_aabb) {
-            let collision = get_collision(&player_aabb, &block_aabb);
-            return Some(events::Collision {
-                block_index: block_index.0,
-                side: collision,
-            });
+    // Standing across two walls pushes the player out of both by the same
+    // amount, so take the largest push per axis rather than their sum.
+    let mut push = Vec2::ZERO;
+    for collision in collision_events.read() {
+        if collision.push.x.abs() > push.x.abs() {
+            push.x = collision.push.x;
+        }
+        if collision.push.y.abs() > push.y.abs() {
+            push.y = collision.push.y;
+        }
+        // Only stop movement into the surface, so the player can still jump
+        // off the floor or walk away from a wall they touch.
+        match collision.side {
+            events::CollisionSide::Left if player_velocity.x > 0.0 => player_velocity.x = 0.0,
+            events::CollisionSide::Right if player_velocity.x < 0.0 => player_velocity.x = 0.0,
+            events::CollisionSide::Top if player_velocity.y < 0.0 => player_velocity.y = 0.0,
+            events::CollisionSide::Bottom if player_velocity.y > 0.0 => player_velocity.y = 0.0,
+            _ => {}
         }
     }
-    None
+    player_transform.translation += push.extend(0.0);
 }
 
-fn overlap(first: &Aabb2d, second: &Aabb2d) -> bool {
-    first.min.x < second.max.x
-        && first.max.x > second.min.x
-        && first.min.y < second.max.y
-        && first.max.y > second.min.y
-}
+/// Separates the player's box from a convex polygon in world coordinates.
+/// Returns the side of the polygon the player hit and the shortest push out
+/// of it, or `None` if they are further than [`CONTACT_SKIN`] apart. Touching
+/// counts as a hit with no push.
+fn separate(player: &Aabb2d, polygon: &[Vec2]) -> Option<(events::CollisionSide, Vec2)> {
+    let center = player.center();
+    let half = player.half_size();
+    let edge_normals = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| (*b - *a).perp().normalize_or_zero());
 
-fn get_collision(first: &Aabb2d, second: &Aabb2d) -> events::CollisionSide {
-    let x_overlap = (first.max.x - second.min.x)
-        .abs()
-        .min((second.max.x - first.min.x).abs());
-    let y_overlap = (first.max.y - second.min.y)
-        .abs()
-        .min((second.max.y - first.min.y).abs());
-
-    if x_overlap < y_overlap {
-        if first.min.x < second.min.x {
-            events::CollisionSide::Left
-        } else {
-            events::CollisionSide::Right
+    // Separating axis test. Vertical pushes win ties, so the player stands on
+    // corners rather than sliding off them.
+    let mut shortest: Option<(f32, Vec2)> = None;
+    for axis in [Vec2::Y, Vec2::X].into_iter().chain(edge_normals) {
+        if axis == Vec2::ZERO {
+            continue;
         }
-    } else {
-        if first.min.y < second.min.y {
-            events::CollisionSide::Bottom
+        let radius = half.x * axis.x.abs() + half.y * axis.y.abs();
+        let projection = center.dot(axis);
+        let (min, max) = polygon
+            .iter()
+            .map(|point| point.dot(axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+        let forward = max - (projection - radius);
+        let backward = projection + radius - min;
+        if forward < -CONTACT_SKIN || backward < -CONTACT_SKIN {
+            return None;
+        }
+        let (depth, direction) = if forward <= backward {
+            (forward, axis)
         } else {
+            (backward, -axis)
+        };
+        if shortest.is_none_or(|(shortest_depth, _)| depth < shortest_depth) {
+            shortest = Some((depth, direction));
+        }
+    }
+
+    let (depth, direction) = shortest?;
+    let side = if direction.y.abs() >= direction.x.abs() {
+        if direction.y > 0.0 {
             events::CollisionSide::Top
+        } else {
+            events::CollisionSide::Bottom
         }
+    } else if direction.x > 0.0 {
+        events::CollisionSide::Right
+    } else {
+        events::CollisionSide::Left
+    };
+    Some((side, direction * depth.max(0.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOOR: [Vec2; 4] = [
+        Vec2::new(-100.0, -10.0),
+        Vec2::new(100.0, -10.0),
+        Vec2::new(100.0, 0.0),
+        Vec2::new(-100.0, 0.0),
+    ];
+
+    fn player_at(y: f32) -> Aabb2d {
+        Aabb2d::new(Vec2::new(0.0, y), Vec2::splat(15.0))
+    }
+
+    #[test]
+    fn overlap_pushes_out_of_the_top() {
+        let (side, push) = separate(&player_at(10.0), &FLOOR).unwrap();
+        assert_eq!(side, events::CollisionSide::Top);
+        assert_eq!(push, Vec2::new(0.0, 5.0));
+    }
+
+    #[test]
+    fn touching_is_contact_without_a_push() {
+        let (side, push) = separate(&player_at(15.0), &FLOOR).unwrap();
+        assert_eq!(side, events::CollisionSide::Top);
+        assert_eq!(push, Vec2::ZERO);
+    }
+
+    #[test]
+    fn a_gap_is_no_contact() {
+        assert!(separate(&player_at(15.0 + 2.0 * CONTACT_SKIN), &FLOOR).is_none());
+    }
+
+    #[test]
+    fn touching_a_side_is_wall_contact() {
+        let player = Aabb2d::new(Vec2::new(115.0, -5.0), Vec2::splat(15.0));
+        let (side, push) = separate(&player, &FLOOR).unwrap();
+        assert_eq!(side, events::CollisionSide::Right);
+        assert_eq!(push, Vec2::ZERO);
     }
 }
@@ -1,89 +1,73 @@
 use crate::components;
 use crate::events;
+use crate::resources::key_bindings::{Action, KeyBindings};
 use bevy::prelude::*;
 
 pub fn player_movement_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
     mut query: Query<
         (&mut components::Velocity, &mut components::PlayerState),
         With<components::Player>,
     >,
     config: Res<crate::resources::json_reader::Config>,
+    mut jump_events: EventWriter<events::PlayerJumped>,
 ) {
-    let (mut player_velocity, mut player_state) = query.single_mut();
+    let Ok((mut player_velocity, mut player_state)) = query.get_single_mut() else {
+        return;
+    };
 
-    if keyboard_input.pressed(KeyCode::KeyA) {
+    if key_bindings.pressed(Action::MoveLeft, &keyboard_input) {
         player_velocity.x = -config.objects.player.speed;
-    } else if keyboard_input.pressed(KeyCode::KeyD) {
+    } else if key_bindings.pressed(Action::MoveRight, &keyboard_input) {
         player_velocity.x = config.objects.player.speed;
     } else {
         player_velocity.x = 0.0;
     }
 
-    if keyboard_input.pressed(KeyCode::Space) && player_state.grounded {
+    if key_bindings.pressed(Action::Jump, &keyboard_input) && player_state.grounded {
         player_velocity.y = config.objects.player.jump_force;
         player_state.grounded = false;
+        jump_events.send(events::PlayerJumped);
     }
 }
 
 pub fn player_on_block_moving_system(
-    mut param_set: ParamSet<(
-        Query<&mut components::Velocity, With<components::Player>>,
-        Query<(&components::Velocity, &components::Block)>,
-    )>,
+    mut player_query: Query<&mut components::Velocity, With<components::Player>>,
+    block_query: Query<
+        &components::Velocity,
+        (With<components::Block>, Without<components::Player>),
+    >,
     mut collision_events: EventReader<events::Collision>,
 ) {
-    let mut collisions_to_handle = Vec::new();
+    let Ok(mut player_velocity) = player_query.get_single_mut() else {
+        return;
+    };
     for collision in collision_events.read() {
-        if let Some((block_velocity, _)) = param_set
-            .p1()
-            .iter()
-            .find(|(_, b)| b.0 == collision.block_index)
-        {
-            collisions_to_handle.push((collision.side, *block_velocity));
+        if collision.side != events::CollisionSide::Top {
+            continue;
         }
-    }
-
-    if let Ok(mut player_velocity) = param_set.p0().get_single_mut() {
-        for (side, block_velocity) in collisions_to_handle {
-            match side {
-                events::CollisionSide::Top => {
-                    player_velocity.x += 2.0 * block_velocity.x;
-                }
-                _ => {}
-            }
+        if let Ok(block_velocity) = block_query.get(collision.entity) {
+            player_velocity.x += 2.0 * block_velocity.x;
         }
     }
 }
 
-pub fn player_bounds_system(
-    mut query: Query<
-        (
-            &mut Transform,
-            &mut components::Velocity,
-            &mut components::PlayerState,
-        ),
-        With<components::Player>,
-    >,
-    config: Res<crate::resources::json_reader::Config>,
+/// Sends [`events::PlayerLanded`] when the player stops falling on a wall or
+/// a block.
+pub fn landing_system(
+    query: Query<(&components::Velocity, &components::PlayerState), With<components::Player>>,
+    mut previous_velocity: Local<f32>,
+    mut landed_events: EventWriter<events::PlayerLanded>,
 ) {
-    for (mut transform, mut velocity, mut player_state) in &mut query {
-        let left_bound = config.wall_params.left_x
-            + config.wall_params.thickness / 2.0
-            + config.objects.player.size / 2.0;
-        let right_bound = config.wall_params.right_x
-            - config.wall_params.thickness / 2.0
-            - config.objects.player.size / 2.0;
-        let bottom_bound = config.wall_params.bottom_y
-            + config.wall_params.thickness / 2.0
-            + config.objects.player.size / 2.0;
-
-        transform.translation.x = transform.translation.x.clamp(left_bound, right_bound);
-        transform.translation.y = transform.translation.y.clamp(bottom_bound, f32::MAX);
-
-        if transform.translation.y == bottom_bound {
-            velocity.y = 0.0;
-            player_state.grounded = true;
-        }
+    let Ok((velocity, player_state)) = query.get_single() else {
+        return;
+    };
+    // Only a stop on solid ground counts, not a respawn mid-fall.
+    if *previous_velocity < 0.0 && velocity.y == 0.0 && player_state.grounded {
+        landed_events.send(events::PlayerLanded {
+            speed: -*previous_velocity,
+        });
     }
+    *previous_velocity = velocity.y;
 }
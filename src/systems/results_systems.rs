@@ -0,0 +1,59 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::states::GameState;
+use bevy::prelude::*;
+
+pub fn level_timer_system(time: Res<Time>, mut timer: ResMut<resources::level_state::LevelTimer>) {
+    if timer.running {
+        timer.elapsed += time.delta();
+    }
+}
+
+pub fn level_complete_system(
+    mut goal_events: EventReader<events::GoalReached>,
+    mut timer: ResMut<resources::level_state::LevelTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if goal_events.is_empty() {
+        return;
+    }
+    goal_events.clear();
+
+    timer.running = false;
+    next_state.set(GameState::Results);
+}
+
+/// Moves on to the next level in the list, or replays the last one.
+pub fn results_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    level_list: Res<resources::level_list::LevelList>,
+    path: Res<resources::json_reader::JsonFilePath>,
+    mut load_events: EventWriter<events::LoadLevel>,
+    mut restart_events: EventWriter<events::RestartLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    match level_list.next_after(&path.0) {
+        Some(next) => {
+            load_events.send(events::LoadLevel {
+                path: next.path.clone(),
+            });
+        }
+        None => {
+            restart_events.send(events::RestartLevel);
+        }
+    }
+    next_state.set(GameState::Playing);
+}
+
+pub fn despawn_results_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<components::ResultsScreen>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,28 @@
+use crate::events;
+use crate::resources;
+use bevy::prelude::*;
+
+/// Records the finished attempt, unlocks the next level and writes the save.
+pub fn record_progress_system(
+    mut goal_events: EventReader<events::GoalReached>,
+    mut save_data: ResMut<resources::save_data::SaveData>,
+    save_path: Res<resources::save_data::SavePath>,
+    path: Res<resources::json_reader::JsonFilePath>,
+    level_list: Res<resources::level_list::LevelList>,
+    timer: Res<resources::level_state::LevelTimer>,
+    score: Res<resources::level_state::Score>,
+) {
+    if goal_events.is_empty() {
+        return;
+    }
+    goal_events.clear();
+
+    save_data.unlock(&path.0);
+    save_data.record(&path.0, timer.elapsed.as_secs_f32(), score.collected);
+    if let Some(next) = level_list.next_after(&path.0) {
+        save_data.unlock(&next.path);
+    }
+    if let Err(err) = save_data.write(&save_path.0) {
+        error!("Unable to write save {}: {err:#}", save_path.0.display());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::components::{FpsText, SettingsButton};
+use crate::resources::key_bindings::{KeyBindings, Rebinding};
+use crate::resources::save_data::{SaveData, SavePath, Settings, RESOLUTIONS};
+use bevy::{
+    audio::{GlobalVolume, Volume},
+    prelude::*,
+    window::{PresentMode, PrimaryWindow, WindowMode},
+};
+
+/// Next volume in 10% steps, wrapping from 100% back to 0%.
+fn next_volume(volume: f32) -> f32 {
+    let step = (volume * 10.0).round() as u32;
+    ((step + 1) % 11) as f32 / 10.0
+}
+
+pub fn settings_button_system(
+    query: Query<(&Interaction, &SettingsButton), Changed<Interaction>>,
+    mut save_data: ResMut<SaveData>,
+    save_path: Res<SavePath>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, button) in &query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let settings = &mut save_data.settings;
+        match button {
+            SettingsButton::WindowMode => {
+                settings.window_mode = match settings.window_mode {
+                    WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+                    WindowMode::BorderlessFullscreen => WindowMode::Fullscreen,
+                    WindowMode::SizedFullscreen | WindowMode::Fullscreen => WindowMode::Windowed,
+                };
+            }
+            SettingsButton::Resolution => {
+                let index = RESOLUTIONS
+                    .iter()
+                    .position(|resolution| *resolution == settings.resolution)
+                    .map_or(0, |index| (index + 1) % RESOLUTIONS.len());
+                settings.resolution = RESOLUTIONS[index];
+            }
+            SettingsButton::Vsync => settings.vsync = !settings.vsync,
+            SettingsButton::MasterVolume => {
+                settings.master_volume = next_volume(settings.master_volume)
+            }
+            SettingsButton::MusicVolume => {
+                settings.music_volume = next_volume(settings.music_volume)
+            }
+            SettingsButton::SfxVolume => settings.sfx_volume = next_volume(settings.sfx_volume),
+            SettingsButton::ShowFps => settings.show_fps = !settings.show_fps,
+            SettingsButton::Rebind(action) => {
+                // Pressing the button again cancels the rebind.
+                rebinding.0 = match rebinding.0 {
+                    Some(waiting) if waiting == *action => None,
+                    _ => Some(*action),
+                };
+                continue;
+            }
+        }
+        if let Err(err) = save_data.write(&save_path.0) {
+            error!("Unable to write save {}: {err:#}", save_path.0.display());
+        }
+    }
+}
+
+/// Binds the next key pressed to the action being rebound. The key is consumed,
+/// so binding e.g. `Escape` doesn't also leave the settings screen.
+pub fn rebind_capture_system(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut save_data: ResMut<SaveData>,
+    save_path: Res<SavePath>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(key) = keyboard_input.get_just_pressed().next().copied() else {
+        return;
+    };
+    keyboard_input.clear_just_pressed(key);
+    save_data.settings.key_bindings.set(action, key);
+    rebinding.0 = None;
+    if let Err(err) = save_data.write(&save_path.0) {
+        error!("Unable to write save {}: {err:#}", save_path.0.display());
+    }
+}
+
+pub fn cancel_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+pub fn settings_label_system(
+    save_data: Res<SaveData>,
+    rebinding: Res<Rebinding>,
+    button_query: Query<(&SettingsButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !save_data.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (button, children) in &button_query {
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = button.label(&save_data.settings, rebinding.0);
+        }
+    }
+}
+
+/// Run condition: the settings in the save differ from the last time it was
+/// checked. Recording progress or a high score doesn't count.
+pub fn settings_changed(save_data: Res<SaveData>, mut last: Local<Option<Settings>>) -> bool {
+    if !save_data.is_changed() || last.as_ref() == Some(&save_data.settings) {
+        return false;
+    }
+    *last = Some(save_data.settings.clone());
+    true
+}
+
+/// Pushes the saved settings to the window, audio and key bindings.
+pub fn apply_settings_system(
+    save_data: Res<SaveData>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    global_volume: Option<ResMut<GlobalVolume>>,
+    mut key_bindings: ResMut<KeyBindings>,
+) {
+    let settings: &Settings = &save_data.settings;
+    if let Ok(mut window) = window_query.get_single_mut() {
+        let [width, height] = settings.resolution;
+        window.mode = settings.window_mode;
+        window.resolution.set(width as f32, height as f32);
+        window.present_mode = if settings.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+    if let Some(mut global_volume) = global_volume {
+        global_volume.volume = Volume::new(settings.master_volume);
+    }
+    *key_bindings = settings.key_bindings.clone();
+}
+
+pub fn fps_visibility_system(
+    save_data: Res<SaveData>,
+    mut query: Query<&mut Visibility, With<FpsText>>,
+) {
+    for mut visibility in &mut query {
+        *visibility = if save_data.settings.show_fps {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
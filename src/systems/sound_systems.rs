@@ -0,0 +1,115 @@
+use crate::components;
+use crate::events;
+use crate::resources;
+use crate::resources::save_data::{SaveData, Settings};
+use crate::resources::sounds::{Sound, SoundEffects};
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+/// Calls `f` with the saved settings, or with the default settings in apps
+/// without save data.
+fn with_settings<T>(save_data: Option<Res<SaveData>>, f: impl FnOnce(&Settings) -> T) -> T {
+    match save_data {
+        Some(save_data) => f(&save_data.settings),
+        None => f(&Settings::default()),
+    }
+}
+
+/// Starts loading every sound effect so the first play isn't delayed.
+pub fn load_sounds_system(asset_server: Res<AssetServer>, mut sounds: ResMut<SoundEffects>) {
+    let handles = sounds
+        .sounds
+        .iter()
+        .map(|(&sound, path)| (sound, asset_server.load(path)))
+        .collect();
+    sounds.handles = handles;
+}
+
+/// Plays a sound for jumps, landings, head bumps, pickups, deaths and goals.
+/// Each sound plays at most once a frame, however many events caused it.
+#[allow(clippy::too_many_arguments)]
+pub fn play_sounds_system(
+    mut commands: Commands,
+    mut jump_events: EventReader<events::PlayerJumped>,
+    mut landed_events: EventReader<events::PlayerLanded>,
+    mut collision_events: EventReader<events::Collision>,
+    mut pickup_events: EventReader<events::CollectiblePickedUp>,
+    mut death_events: EventReader<events::PlayerDied>,
+    mut goal_events: EventReader<events::GoalReached>,
+    sounds: Res<SoundEffects>,
+    save_data: Option<Res<SaveData>>,
+) {
+    let bumps = collision_events
+        .read()
+        .filter(|collision| collision.side == events::CollisionSide::Bottom)
+        .count();
+    let played = [
+        (Sound::Jump, jump_events.read().count()),
+        (Sound::Land, landed_events.read().count()),
+        (Sound::Bump, bumps),
+        (Sound::Pickup, pickup_events.read().count()),
+        (Sound::Death, death_events.read().count()),
+        (Sound::Goal, goal_events.read().count()),
+    ];
+
+    if played.iter().all(|&(_, count)| count == 0) {
+        return;
+    }
+
+    let volume = Volume::new(with_settings(save_data, |settings| settings.sfx_volume));
+    for (sound, count) in played {
+        if count == 0 {
+            continue;
+        }
+        if let Some(handle) = sounds.handles.get(&sound) {
+            commands.spawn(AudioBundle {
+                source: handle.clone(),
+                settings: PlaybackSettings::DESPAWN.with_volume(volume),
+            });
+        }
+    }
+}
+
+/// Starts the current level's music, unless it is already playing, and stops
+/// the previous level's.
+pub fn level_music_system(
+    mut commands: Commands,
+    config: Res<resources::json_reader::Config>,
+    save_data: Option<Res<SaveData>>,
+    asset_server: Res<AssetServer>,
+    music_query: Query<(Entity, &components::LevelMusic)>,
+) {
+    if music_query
+        .iter()
+        .any(|(_, music)| Some(&music.path) == config.music.as_ref())
+    {
+        return;
+    }
+    for (entity, _) in &music_query {
+        commands.entity(entity).despawn();
+    }
+    if let Some(path) = &config.music {
+        let volume = with_settings(save_data, |settings| settings.music_volume);
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load(path),
+                settings: PlaybackSettings::LOOP.with_volume(Volume::new(volume)),
+            },
+            components::LevelMusic { path: path.clone() },
+        ));
+    }
+}
+
+/// Applies volume changes from the settings to the music that is playing.
+/// Sound effects are short enough to pick up the new volume on their next
+/// play.
+pub fn music_volume_system(
+    save_data: Res<SaveData>,
+    music_query: Query<&AudioSink, With<components::LevelMusic>>,
+) {
+    let settings = &save_data.settings;
+    for sink in &music_query {
+        // The global volume only applies when playback starts.
+        sink.set_volume(settings.master_volume * settings.music_volume);
+    }
+}
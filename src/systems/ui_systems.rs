@@ -1,4 +1,6 @@
-use crate::components::FpsText;
+use crate::components::{DeathText, FpsText, HeightText, ScoreText, TimeText};
+use crate::resources::level_state::{DeathCount, LevelTimer, Score};
+use crate::resources::theme::Theme;
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     prelude::*,
@@ -7,10 +9,81 @@ pub fn text_update_system(
     diagnostics: Res<DiagnosticsStore>,
     mut query: Query<&mut Text, With<FpsText>>,
 ) {
-    let mut text = query.single_mut();
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
     if let Some(fps) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS) {
         if let Some(value) = fps.smoothed() {
             text.sections[1].value = format!("{value:.2}");
         }
     }
 }
+
+pub fn death_text_update_system(
+    death_count: Res<DeathCount>,
+    mut query: Query<&mut Text, With<DeathText>>,
+) {
+    if !death_count.is_changed() {
+        return;
+    }
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    text.sections[1].value = death_count.0.to_string();
+}
+
+pub fn score_text_update_system(score: Res<Score>, mut query: Query<&mut Text, With<ScoreText>>) {
+    if !score.is_changed() {
+        return;
+    }
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    text.sections[1].value = format!("{} ({}/{})", score.points, score.collected, score.total);
+}
+
+pub fn time_text_update_system(
+    timer: Res<LevelTimer>,
+    mut query: Query<&mut Text, With<TimeText>>,
+) {
+    if !timer.is_changed() {
+        return;
+    }
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    text.sections[1].value = format!("{:.1}", timer.elapsed.as_secs_f32());
+}
+
+/// Restyles the HUD text when the level brings a different theme. The first
+/// section of each line is the label, the rest are highlighted values.
+#[allow(clippy::type_complexity)]
+pub fn hud_theme_system(
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    mut query: Query<
+        &mut Text,
+        Or<(
+            With<FpsText>,
+            With<DeathText>,
+            With<ScoreText>,
+            With<TimeText>,
+            With<HeightText>,
+        )>,
+    >,
+) {
+    let font = asset_server.load(&theme.hud.font);
+    for mut text in &mut query {
+        for (index, section) in text.sections.iter_mut().enumerate() {
+            // Sections drawn in Bevy's built-in font keep it.
+            if section.style.font != Handle::default() {
+                section.style.font = font.clone();
+            }
+            section.style.color = if index == 0 {
+                Color::srgb_from_array(theme.hud.color)
+            } else {
+                theme.highlight()
+            };
+        }
+    }
+}